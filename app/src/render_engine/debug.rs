@@ -105,7 +105,10 @@ pub fn draw_debug(
         if let Some(cursor) = cursor {
             use engine::ecs::generic::pathfinding::Objective;
             let Some(result) = engine::ecs::generic::pathfinding::compute_path(
-                lyon::math::Point::new(cursor.x, cursor.y),
+                &engine::ecs::generic::PositionComponent {
+                    point: lyon::math::Point::new(cursor.x, cursor.y),
+                    radius: 48.0,
+                },
                 &Objective::Unit(engine::ecs::entity::EntityBuilder::guid(
                     &engine::ecs::structures::turret::TurretIndex::RED_MID_OUTER,
                 )),
@@ -25,9 +25,7 @@ pub struct EngineRenderer {
 impl EngineRenderer {
     pub fn game_start() -> Self {
         let mut builder = EntityStoreBuilder::new();
-        let mut engine = MinimapEngine {
-            timer: GameTimer::GAME_START,
-        };
+        let mut engine = MinimapEngine::new();
         engine::Engine::on_start(&mut engine, &mut builder);
         let mut store = builder.build();
         // TODO: adapt
@@ -6,13 +6,19 @@ use geojson::FeatureCollection;
 use crate::{
     ecs::{
         entity::{Entity, SpecificComponent},
-        generic::{pathfinding::PathfindingComponent, PositionComponent},
+        generic::{
+            pathfinding::{LanePaths, PathfindingComponent},
+            PositionComponent,
+        },
         store::EntityStore,
         UnitId,
     },
     nav_engine::{CollisionBox, NavigationMap},
-    structures::{inhibitor::InhibitorComponent, turret::TurretComponent},
-    units::minion::MinionComponent,
+    structures::{inhibitor::InhibitorComponent, nexus::NexusComponent, turret::TurretComponent},
+    units::{
+        camp::CampComponent, champion::ChampionComponent, minion::MinionComponent,
+        monster::MonsterComponent,
+    },
 };
 
 use super::entity::{EntityBuilder, SpecificComponentBuilder};
@@ -24,8 +30,12 @@ pub struct EntityStoreBuilder {
     position: slab::Slab<WithId<PositionComponent>>,
     turret: slab::Slab<WithId<TurretComponent>>,
     inhibitor: slab::Slab<WithId<InhibitorComponent>>,
+    nexus: slab::Slab<WithId<NexusComponent>>,
     pathfinding: slab::Slab<WithId<PathfindingComponent>>,
     minions: slab::Slab<WithId<MinionComponent>>,
+    camps: slab::Slab<WithId<CampComponent>>,
+    monsters: slab::Slab<WithId<MonsterComponent>>,
+    champions: slab::Slab<WithId<ChampionComponent>>,
     map: FeatureCollection,
     nopath_key: usize,
 }
@@ -39,7 +49,11 @@ impl EntityStoreBuilder {
             position: slab::Slab::with_capacity(64),
             turret: slab::Slab::with_capacity(64),
             inhibitor: slab::Slab::with_capacity(64),
+            nexus: slab::Slab::with_capacity(2),
             minions: slab::Slab::with_capacity(8 * 3 * 2 * 3), // max none degenerate case: 8 minions per wave, 3 waves per lane at most, 2 teams, 3 lanes
+            camps: slab::Slab::with_capacity(16),
+            monsters: slab::Slab::with_capacity(4),
+            champions: slab::Slab::with_capacity(10),
             pathfinding,
             map: FeatureCollection {
                 bbox: None,
@@ -63,16 +77,32 @@ impl EntityStoreBuilder {
             SpecificComponentBuilder::Inhibitor(inhib) => {
                 SpecificComponent::Inhibitor(self.inhibitor.insert((guid, inhib)))
             }
+            SpecificComponentBuilder::Nexus(nexus) => {
+                SpecificComponent::Nexus(self.nexus.insert((guid, nexus)))
+            }
             SpecificComponentBuilder::Minion(minion) => {
                 SpecificComponent::Minion(self.minions.insert((guid, minion)))
             }
+            SpecificComponentBuilder::Camp(camp) => {
+                SpecificComponent::Camp(self.camps.insert((guid, camp)))
+            }
+            SpecificComponentBuilder::Monster(monster) => {
+                SpecificComponent::Monster(self.monsters.insert((guid, monster)))
+            }
+            SpecificComponentBuilder::Champion(champion) => {
+                SpecificComponent::Champion(self.champions.insert((guid, champion)))
+            }
         };
 
         let components = Entity {
             guid,
             position: self.position.insert((guid, position)),
             specific,
-            pathfinding: if pathfinding.is_static() {
+            // units able to move keep their own component, even while standing still
+            pathfinding: if pathfinding.is_static()
+                && pathfinding.objectives.is_empty()
+                && pathfinding.speed == 0.0
+            {
                 self.nopath_key
             } else {
                 self.pathfinding.insert((guid, pathfinding))
@@ -96,8 +126,8 @@ impl EntityStoreBuilder {
         
         let triangulation = flexbuffers::from_slice(&buff[..]).unwrap();
         
-        let nav = NavigationMap {
-            tree: rstar::RTree::bulk_load(
+        let nav = NavigationMap::new(
+            rstar::RTree::bulk_load(
                 self.position
                     .iter()
                     .map(|(_, (guid, data))| CollisionBox::Unit {
@@ -113,15 +143,24 @@ impl EntityStoreBuilder {
                     .collect(),
             ),
             triangulation,
-        };
+        );
+        let lanes = LanePaths::new(&nav);
+
         EntityStore {
             entities: self.entities,
             position: self.position,
             turrets: self.turret,
             inhibitors: self.inhibitor,
+            nexuses: self.nexus,
             pathfinding: self.pathfinding,
             minions: self.minions,
+            camps: self.camps,
+            monsters: self.monsters,
+            champions: self.champions,
+            gold: HashMap::new(),
+            experience: HashMap::new(),
             nav,
+            lanes,
         }
     }
 }
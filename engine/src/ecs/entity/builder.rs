@@ -3,15 +3,22 @@ use crate::{
         generic::{pathfinding::PathfindingComponent, PositionComponent},
         UnitId,
     },
-    structures::{inhibitor::InhibitorComponent, turret::TurretComponent},
-    units::minion::MinionComponent,
+    structures::{inhibitor::InhibitorComponent, nexus::NexusComponent, turret::TurretComponent},
+    units::{
+        camp::CampComponent, champion::ChampionComponent, minion::MinionComponent,
+        monster::MonsterComponent,
+    },
 };
 
 pub enum SpecificComponentBuilder {
     None,
     Turret(TurretComponent),
     Inhibitor(InhibitorComponent),
+    Nexus(NexusComponent),
     Minion(MinionComponent),
+    Camp(CampComponent),
+    Monster(MonsterComponent),
+    Champion(ChampionComponent),
 }
 
 pub trait EntityBuilder {
@@ -21,3 +28,25 @@ pub trait EntityBuilder {
     fn specific(&self) -> SpecificComponentBuilder;
 }
 
+/// Stand-in for a champion, a unit without a specific component.
+#[cfg(test)]
+pub(crate) struct TestChampion(pub(crate) crate::core::Team, pub(crate) lyon::math::Point);
+
+#[cfg(test)]
+impl EntityBuilder for TestChampion {
+    fn guid(&self) -> UnitId {
+        UnitId::new(Some(self.0), None)
+    }
+    fn position(&self) -> PositionComponent {
+        PositionComponent {
+            point: self.1,
+            radius: 65.0,
+        }
+    }
+    fn pathfinding(&self) -> PathfindingComponent {
+        PathfindingComponent::no_path()
+    }
+    fn specific(&self) -> SpecificComponentBuilder {
+        SpecificComponentBuilder::None
+    }
+}
@@ -5,7 +5,10 @@ use crate::{
     core::Team,
     ecs::{
         generic::{
-            pathfinding::{PathfindError, PathfindingComponent},
+            pathfinding::{
+                compute_path, ArcLengths, Objective, PathfindError, Pathfinding,
+                PathfindingComponent,
+            },
             PositionComponent,
         },
         store::EntityStore,
@@ -16,13 +19,19 @@ use crate::{
 
 mod builder;
 pub use builder::{EntityBuilder, SpecificComponentBuilder};
+#[cfg(test)]
+pub(crate) use builder::TestChampion;
 
 #[derive(Debug, Clone)]
 pub enum SpecificComponent {
     None,
     Turret(usize),
     Inhibitor(usize),
+    Nexus(usize),
     Minion(usize),
+    Camp(usize),
+    Monster(usize),
+    Champion(usize),
 }
 
 #[derive(Debug, Clone)]
@@ -41,9 +50,25 @@ impl Entity {
     pub fn is_inhib(&self) -> bool {
         matches!(self.specific, SpecificComponent::Inhibitor(_))
     }
+
+    pub fn is_nexus(&self) -> bool {
+        matches!(self.specific, SpecificComponent::Nexus(_))
+    }
     pub(crate) fn is_minion(&self) -> bool {
         matches!(self.specific, SpecificComponent::Minion(_))
     }
+
+    pub fn is_camp(&self) -> bool {
+        matches!(self.specific, SpecificComponent::Camp(_))
+    }
+
+    pub fn is_monster(&self) -> bool {
+        matches!(self.specific, SpecificComponent::Monster(_))
+    }
+
+    pub fn is_champion(&self) -> bool {
+        matches!(self.specific, SpecificComponent::Champion(_))
+    }
 }
 
 pub trait EntityRef<'store> {
@@ -105,7 +130,11 @@ pub(crate) trait EntityRefCrateExt<'store>: EntityRef<'store> {
         match self.entity().specific {
             SpecificComponent::Turret(a) => Some(a),
             SpecificComponent::Inhibitor(a) => Some(a),
+            SpecificComponent::Nexus(a) => Some(a),
             SpecificComponent::Minion(a) => Some(a),
+            SpecificComponent::Camp(a) => Some(a),
+            SpecificComponent::Monster(a) => Some(a),
+            SpecificComponent::Champion(a) => Some(a),
             SpecificComponent::None => None,
         }
     }
@@ -153,48 +182,78 @@ pub trait EntityMut<'store>: EntityRef<'store> {
     ) -> Result<Option<lyon::math::Point>, PathfindError> {
         let component = self.pathfinding_component_mut();
 
-        match &component.path {
-            super::generic::pathfinding::Pathfinding::Static => Ok(None),
-            super::generic::pathfinding::Pathfinding::Persistent(path) => {
-                let maxpos = lyon::algorithms::length::approximate_length(path.iter(), 0.1);
+        if !component.is_dynamic() && !component.objectives.is_empty() {
+            self.next_objective();
+        }
 
-                let newpos = component.position + (duration.as_secs_f32() * component.speed);
-                if newpos >= maxpos {
-                    let point = path.last_endpoint().unwrap().0;
-                    self.move_to(point);
-                    return Err(PathfindError::EndReached(point));
-                }
-                component.position = newpos;
-
-                let mut position = None;
-                let mut pattern = lyon::algorithms::walk::RegularPattern {
-                    callback: &mut |event: lyon::algorithms::walk::WalkerEvent| {
-                        position = Some(lyon::math::Point::new(event.position.x, event.position.y));
-                        false
-                    },
-                    interval: component.speed as f32,
+        let computed;
+        let (lengths, end) = match &component.path {
+            Pathfinding::Static => return Ok(None),
+            Pathfinding::Persistent(path) => {
+                let lengths = match self.store_ref().lanes.lengths(path) {
+                    Some(lengths) => lengths,
+                    // persistent paths that are not lanes are measured on every step
+                    None => {
+                        computed = ArcLengths::new(path);
+                        &computed
+                    }
                 };
-                lyon::algorithms::walk::walk_along_path(
-                    path.iter(),
-                    component.position,
-                    0.1,
-                    &mut pattern,
-                );
-                if let Some(point) = position {
-                    self.move_to(point);
+                (lengths, path.last_endpoint().unwrap().0)
+            }
+            Pathfinding::Dynamic { lengths, end, .. } => (lengths, *end),
+        };
+
+        let maxpos = lengths.length();
+
+        let newpos = component.position + (duration.as_secs_f32() * component.speed);
+        if newpos >= maxpos {
+            self.move_to(end);
+            if component.is_dynamic() {
+                // dynamic paths are one-shot, the unit stands still once it arrived
+                component.path = Pathfinding::Static;
+                component.position = 0.0;
+
+                // walk towards the next objective for the time left in this step
+                if self.next_objective().is_some() && component.speed > 0.0 {
+                    let left = (newpos - maxpos) / component.speed;
+                    return self.pathfind_for_duration(crate::core::GameTimer(
+                        std::time::Duration::from_secs_f32(left),
+                    ));
                 }
-                Ok(position)
             }
-            super::generic::pathfinding::Pathfinding::Dynamic {
-                path: _path,
-                start: _start,
-                end: _end,
-            } => {
-                unimplemented!(
-                    "maybe change start/end to a set duration after which no pathfinding is done"
-                )
+            return Err(PathfindError::EndReached(end));
+        }
+        component.position = newpos;
+
+        let position = lengths.point_at(component.position);
+        if let Some(point) = position {
+            self.move_to(point);
+        }
+        Ok(position)
+    }
+
+    /// Compute a path from the current position to `target` and follow it on subsequent calls to
+    /// [`EntityMut::pathfind_for_duration`]. Returns the destination or `None` if it is unreachable.
+    fn pathfind_to(&self, target: &Objective) -> Option<lyon::math::Point> {
+        let result = compute_path(self.position_component(), target, self.store_ref())?;
+        let path = result.to_path(self.store_ref());
+
+        self.pathfinding_component_mut().follow(path)
+    }
+
+    /// Queue `objective` after the ones already scheduled for this unit.
+    fn add_objective(&self, objective: Objective) {
+        self.pathfinding_component_mut().add_objective(objective)
+    }
+
+    /// Pop objectives until a reachable one is found and start following it.
+    fn next_objective(&self) -> Option<lyon::math::Point> {
+        while let Some(objective) = self.pathfinding_component_mut().objectives.pop_back() {
+            if let Some(end) = self.pathfind_to(&objective) {
+                return Some(end);
             }
         }
+        None
     }
 
     fn delete(self) -> Result<UnitId, String>
@@ -205,6 +264,26 @@ pub trait EntityMut<'store>: EntityRef<'store> {
     }
 }
 
+pub struct UnitMut<'store> {
+    pub(crate) store: &'store mut EntityStore,
+    pub(crate) entity: std::ptr::NonNull<Entity>,
+}
+
+impl<'store> EntityRef<'store> for UnitMut<'store> {
+    fn store_ref(&self) -> &'store EntityStore {
+        unsafe { &*(self.store as *const _) }
+    }
+    fn entity(&self) -> &'store Entity {
+        unsafe { self.entity.as_ref() }
+    }
+}
+
+impl<'store> EntityMut<'store> for UnitMut<'store> {
+    fn store_mut(&self) -> &'store mut EntityStore {
+        unsafe { &mut *(self.store as *const _ as *mut _) }
+    }
+}
+
 pub(crate) trait EntityMutCrateExt<'store>: EntityMut<'store> {
     fn position_component_mut(&self) -> &'store mut PositionComponent {
         &mut self.store_mut().position[self.entity().position].1
@@ -216,3 +295,95 @@ pub(crate) trait EntityMutCrateExt<'store>: EntityMut<'store> {
 }
 impl<'store, T> EntityRefCrateExt<'store> for T where T: EntityRef<'store> + ?Sized {}
 impl<'store, T> EntityMutCrateExt<'store> for T where T: EntityMut<'store> + ?Sized {}
+
+#[test]
+fn units_follow_dynamic_paths_to_their_end() {
+    use crate::core::{GameTimer, Lane};
+
+    let (_, mut store) = crate::MinimapEngine::init();
+    let lane = store
+        .lanes
+        .lengths(&store.lanes[(Team::Blue, Lane::Mid)])
+        .unwrap();
+    let (from, to) = (
+        lane.point_at(1000.0).unwrap(),
+        lane.point_at(3000.0).unwrap(),
+    );
+    let id = store.spawn(TestChampion(Team::Blue, from));
+
+    let unit = store.get_unit_mut(id).unwrap();
+    unit.pathfinding_component_mut().speed = 300.0;
+    let end = unit.pathfind_to(&Objective::Position(to)).unwrap();
+    assert!((end - to).length() < 1.0);
+
+    let Pathfinding::Dynamic { lengths, start, .. } = &unit.pathfinding_component().path else {
+        panic!("the unit should follow a dynamic path");
+    };
+    assert!((*start - from).length() < 1.0);
+    let (length, walked) = (lengths.length(), lengths.point_at(300.0).unwrap());
+    assert!(length >= (to - from).length() - 1.0);
+
+    // the unit advances along the path at its speed
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    assert_eq!(unit.pathfind_for_duration(second).unwrap(), Some(walked));
+    assert_eq!(*unit.position(), walked);
+    assert_eq!(unit.pathfinding_component().position, 300.0);
+
+    // and stops at the end of the path, standing still from then on
+    let rest = GameTimer(std::time::Duration::from_secs_f32(length / 300.0));
+    assert!(matches!(
+        unit.pathfind_for_duration(rest),
+        Err(PathfindError::EndReached(point)) if point == end
+    ));
+    assert_eq!(*unit.position(), end);
+    assert!(unit.pathfinding_component().is_static());
+    assert_eq!(unit.pathfind_for_duration(second).unwrap(), None);
+}
+
+#[test]
+fn queued_objectives_are_followed_in_order() {
+    use crate::core::{GameTimer, Lane};
+
+    let end = |unit: &UnitMut| match &unit.pathfinding_component().path {
+        Pathfinding::Dynamic { end, lengths, .. } => Some((*end, lengths.length())),
+        _ => None,
+    };
+
+    let (_, mut store) = crate::MinimapEngine::init();
+    let lane = store
+        .lanes
+        .lengths(&store.lanes[(Team::Blue, Lane::Mid)])
+        .unwrap();
+    let [from, first, last] = [1000.0, 3000.0, 500.0].map(|d| lane.point_at(d).unwrap());
+    let id = store.spawn(TestChampion(Team::Blue, from));
+
+    let unit = store.get_unit_mut(id).unwrap();
+    unit.pathfinding_component_mut().speed = 300.0;
+    unit.add_objective(Objective::Position(first));
+    unit.add_objective(Objective::Position(last));
+    assert!(unit.pathfinding_component().is_static());
+
+    // the objective queued first is followed first
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    assert!(unit.pathfind_for_duration(second).unwrap().is_some());
+    let (to, length) = end(&unit).unwrap();
+    assert!((to - first).length() < 1.0);
+    assert_eq!(unit.pathfinding_component().objectives.len(), 1);
+
+    // time left after reaching an objective is spent walking to the next one
+    let rest = (length - unit.pathfinding_component().position) / 300.0;
+    let step = GameTimer(std::time::Duration::from_secs_f32(rest + 1.0));
+    let walked = unit.pathfind_for_duration(step).unwrap().unwrap();
+    let (to, length) = end(&unit).unwrap();
+    assert!((to - last).length() < 1.0);
+    assert!((unit.pathfinding_component().position - 300.0).abs() < 1.0);
+    assert!((walked - first).length() > 299.0);
+    assert!(unit.pathfinding_component().objectives.is_empty());
+
+    let rest = GameTimer(std::time::Duration::from_secs_f32(length / 300.0));
+    assert!(matches!(
+        unit.pathfind_for_duration(rest),
+        Err(PathfindError::EndReached(point)) if point == to
+    ));
+    assert!(unit.pathfinding_component().is_static());
+}
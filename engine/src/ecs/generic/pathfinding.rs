@@ -1,32 +1,110 @@
-use std::{
-    collections::{HashSet, LinkedList},
-    ops::Index,
-    sync::{Arc, LazyLock},
-};
-
-use lyon::{
-    math::Point,
-};
+use std::{collections::LinkedList, ops::Index, sync::Arc};
 
+use lyon::math::Point;
 
 use crate::{
-    core::{GameTimer, Lane, Team},
-    ecs::store::EntityStore,
+    core::{Lane, Team},
+    ecs::{generic::PositionComponent, store::EntityStore},
+    nav_engine::{FaceId, NavigationMap},
 };
 
-pub static LANE_PATHS: LazyLock<LanePaths> = LazyLock::new(|| LanePaths {
-    paths: [
-        Arc::new(crate::core::top_lane_path(crate::core::Team::Blue)),
-        Arc::new(crate::core::mid_lane_path(crate::core::Team::Blue)),
-        Arc::new(crate::core::bot_lane_path(crate::core::Team::Blue)),
-        Arc::new(crate::core::top_lane_path(crate::core::Team::Red)),
-        Arc::new(crate::core::mid_lane_path(crate::core::Team::Red)),
-        Arc::new(crate::core::bot_lane_path(crate::core::Team::Red)),
-    ],
-});
+/// Distance kept between the lanes and the walls, the radius of the largest minions.
+const LANE_RADIUS: f32 = 65.0;
 
+/// Paths followed by the minions of each lane, derived from the navmesh of a store when it is
+/// built.
 pub struct LanePaths {
     paths: [Arc<lyon::path::Path>; 6],
+    lengths: [ArcLengths; 6],
+}
+
+impl LanePaths {
+    pub fn new(nav: &NavigationMap) -> Self {
+        let [top, mid, bot] = [Lane::Top, Lane::Mid, Lane::Bot].map(|lane| {
+            lane_path(nav, lane).unwrap_or_else(|| panic!("no path along the {lane:?} lane"))
+        });
+        // red minions walk the blue lanes backwards
+        let [red_top, red_mid, red_bot] =
+            [&top, &mid, &bot].map(|path| path.reversed().with_attributes().into_path());
+
+        let paths = [top, mid, bot, red_top, red_mid, red_bot].map(Arc::new);
+        Self {
+            lengths: std::array::from_fn(|i| ArcLengths::new(&paths[i])),
+            paths,
+        }
+    }
+
+    /// Arc length table of a lane path, `None` for paths that are not lanes.
+    pub fn lengths(&self, path: &Arc<lyon::path::Path>) -> Option<&ArcLengths> {
+        self.paths
+            .iter()
+            .position(|lane| Arc::ptr_eq(lane, path))
+            .map(|i| &self.lengths[i])
+    }
+}
+
+/// Path from the blue nexus to the red one going past every turret of `lane`, keeping the largest
+/// minions away from the walls.
+fn lane_path(nav: &NavigationMap, lane: Lane) -> Option<lyon::path::Path> {
+    use spade::{Point2, Triangulation};
+
+    use crate::{
+        ecs::entity::EntityBuilder,
+        structures::{
+            nexus::NexusIndex,
+            turret::{TurretIndex, TurretKind},
+        },
+    };
+
+    let turrets =
+        |team, kinds: [TurretKind; 3]| kinds.map(|kind| TurretIndex(team, lane, kind).position());
+    let waypoints = turrets(
+        Team::Blue,
+        [TurretKind::Inhib, TurretKind::Inner, TurretKind::Outer],
+    )
+    .into_iter()
+    .chain(turrets(
+        Team::Red,
+        [TurretKind::Outer, TurretKind::Inner, TurretKind::Inhib],
+    ))
+    .chain(Some(NexusIndex::from(Team::Red).position()));
+
+    let nexus = NexusIndex::from(Team::Blue).position().point;
+    let start = nav.closest_walkable_face(Point2::new(nexus.x as f64, nexus.y as f64))?;
+    let from = nav.triangulation.cdt.face(start).center();
+
+    // chain the channels leading from one turret to the next and smooth them all at once
+    let (mut channel, mut to) = (vec![start], from);
+    for waypoint in waypoints {
+        let agent = PositionComponent {
+            point: Point::new(to.x as f32, to.y as f32),
+            radius: LANE_RADIUS,
+        };
+        let mut leg = PathfindInstance::new(&agent, (waypoint.point, waypoint.radius), nav)?;
+        // the end of the previous leg may lie on an edge, keep walking from the face it ended on
+        leg.start = *channel.last()?;
+        let leg = leg.path()?;
+        channel.extend(&leg.channel[1..]);
+        to = Point2::new(leg.target.x as f64, leg.target.y as f64);
+    }
+
+    Some(polyline(
+        nav.string_pull(from, to, &channel, LANE_RADIUS)
+            .into_iter()
+            .map(|p| Point::new(p.x as f32, p.y as f32)),
+    ))
+}
+
+fn polyline(mut points: impl Iterator<Item = Point>) -> lyon::path::Path {
+    let mut builder = lyon::path::Path::builder();
+    if let Some(first) = points.next() {
+        builder.begin(first);
+        points.for_each(|p| {
+            builder.line_to(p);
+        });
+        builder.end(false);
+    }
+    builder.build()
 }
 
 impl Index<(Team, Lane)> for LanePaths {
@@ -46,11 +124,105 @@ impl Index<(Team, Lane)> for LanePaths {
     }
 }
 
+/// Tolerance used when flattening the curves of a path.
+const FLATTENING_TOLERANCE: f32 = 0.1;
+
+/// Cumulative length at each point of a flattened path, the position at a given distance along
+/// the path is found with a binary search.
+#[derive(Debug, Clone)]
+pub struct ArcLengths {
+    points: Vec<Point>,
+    lengths: Vec<f32>,
+}
+
+impl ArcLengths {
+    pub fn new(path: &lyon::path::Path) -> Self {
+        use lyon::path::{iterator::PathIterator, PathEvent};
+
+        let (mut points, mut lengths) = (Vec::<Point>::new(), Vec::<f32>::new());
+        for event in path.iter().flattened(FLATTENING_TOLERANCE) {
+            // subpaths are walked one after the other, jumps between them are free
+            let (point, jump) = match event {
+                PathEvent::Begin { at } => (at, true),
+                PathEvent::Line { to, .. } => (to, false),
+                PathEvent::End {
+                    first, close: true, ..
+                } => (first, false),
+                _ => continue,
+            };
+            let step = match points.last() {
+                Some(last) if !jump => (point - *last).length(),
+                _ => 0.0,
+            };
+            lengths.push(lengths.last().copied().unwrap_or(0.0) + step);
+            points.push(point);
+        }
+        Self { points, lengths }
+    }
+
+    /// Total length of the path.
+    pub fn length(&self) -> f32 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    /// Position after walking `distance` along the path, clamped to its ends.
+    pub fn point_at(&self, distance: f32) -> Option<Point> {
+        let next = self.lengths.partition_point(|length| *length < distance);
+        match next {
+            0 => self.points.first().copied(),
+            next if next == self.points.len() => self.points.last().copied(),
+            next => {
+                let (from, to) = (self.lengths[next - 1], self.lengths[next]);
+                let t = (distance - from) / (to - from);
+                Some(self.points[next - 1].lerp(self.points[next], t))
+            }
+        }
+    }
+
+    /// Distance along the path of its point closest to `point`.
+    pub fn project(&self, point: Point) -> Option<f32> {
+        let closest = |i: usize| {
+            let (from, to) = (self.points[i], self.points[i + 1]);
+            let segment = to - from;
+            // jumps between subpaths are not part of the path
+            let t = if self.lengths[i + 1] > self.lengths[i] {
+                ((point - from).dot(segment) / segment.square_length()).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let along = self.lengths[i] + t * (self.lengths[i + 1] - self.lengths[i]);
+            ((from.lerp(to, t) - point).length(), along)
+        };
+        match self.points.len() {
+            0 => None,
+            1 => Some(0.0),
+            len => (0..len - 1)
+                .map(closest)
+                .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, along)| along),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Objective {
     Unit(crate::ecs::UnitId),
     Position(Point),
 }
 
+impl Objective {
+    /// Position of the objective along with the radius of the unit standing there.
+    pub fn position(&self, store: &EntityStore) -> Option<(Point, f32)> {
+        match self {
+            Objective::Unit(id) => {
+                let target = store.position.get(store.get_raw_by_id(*id)?.position)?.1;
+                Some((target.point, target.radius))
+            }
+            Objective::Position(p) => Some((*p, 0.0)),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 pub struct PointE {
     pub x: f32,
@@ -65,248 +237,345 @@ impl std::hash::Hash for PointE {
 }
 impl Eq for PointE {}
 
+/// Fixed point precision of the A* costs, in cost units per game unit.
+const COST_PRECISION: f64 = 1000.0;
+
+/// Node of the A* search, the faces of the navmesh and the target itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SearchNode {
+    Face(FaceId),
+    Target,
+}
+
 pub struct PathfindInstance<'a> {
     from: PointE,
     to: PointE,
-    store: &'a EntityStore,
+    start: FaceId,
+    /// Face under the target, `None` when the target stands on a wall.
+    target: Option<FaceId>,
+    /// Distance from the target at which it is reached.
+    reach: f64,
+    radius: f32,
+    nav: &'a NavigationMap,
 }
 
-type TargetVertices<'a> = HashSet<
-    spade::handles::FaceHandle<
-        'a,
-        spade::handles::InnerTag,
-        spade::Point2<f64>,
-        (),
-        spade::CdtEdge<()>,
-        (),
-    >,
->;
-
 impl<'a> PathfindInstance<'a> {
-    fn get_neighbours(&self, vertex: &PointE) -> impl Iterator<Item = (PointE, i64)> + '_ {
-        use spade::{Point2, Triangulation};
+    /// Search for an agent occupying `from` to reach a unit of `target_radius` at `target_pos`.
+    /// Positions in walls or structures are moved to the closest walkable point.
+    fn new(
+        from: &PositionComponent,
+        (target_pos, target_radius): (Point, f32),
+        nav: &'a NavigationMap,
+    ) -> Option<Self> {
+        use spade::Point2;
 
-        let position = self
-            .store
-            .nav
-            .triangulation
-            .cdt
-            .locate(Point2::new(vertex.x as f64, vertex.y as f64));
-
-        match position {
-            spade::PositionInTriangulation::OnFace(face) => {
-                let face = self.store.nav.triangulation.cdt.face(face);
-                let vertices = face.adjacent_edges().into_iter().filter_map(|edge| {
-                    self.store
-                        .nav
-                        .triangulation
-                        .keep_vertex(&edge.from())
-                        .then_some(edge.from())
-                });
-
-                Box::new(
-                    vertices
-                        .clone()
-                        .flat_map(|v| v.as_voronoi_face().adjacent_edges())
-                        .flat_map(|edge| edge.as_undirected().vertices())
-                        .filter_map(|v| v.position())
-                        .chain(vertices.clone().map(|v| v.position())),
-                ) as Box<dyn Iterator<Item = Point2<f64>>>
-            }
-            spade::PositionInTriangulation::OnVertex(v) => {
-                let v = self.store.nav.triangulation.cdt.vertex(v);
-                Box::new(
-                    v.as_voronoi_face()
-                        .adjacent_edges()
-                        .flat_map(|edge| edge.as_undirected().vertices())
-                        .filter_map(|v| v.position()),
-                )
-            }
-            spade::PositionInTriangulation::OnEdge(e) => {
-                let e = self.store.nav.triangulation.cdt.directed_edge(e);
-                Box::new(
-                    e.vertices()
-                        .into_iter()
-                        .flat_map(|v| v.as_voronoi_face().adjacent_edges())
-                        .flat_map(|edge| edge.as_undirected().vertices())
-                        .filter_map(|v| v.position()),
-                )
-            }
-            _ => Box::new(std::iter::empty()),
+        let start = nav.walkable_face(Point2::new(from.point.x as f64, from.point.y as f64))?;
+        let target_pos = match target_radius <= 0.0 && !nav.is_walkable(target_pos) {
+            true => nav.nearest_walkable(target_pos)?,
+            false => target_pos,
+        };
+        let target = nav.walkable_face(Point2::new(target_pos.x as f64, target_pos.y as f64));
+        if target.is_none() && target_radius <= 0.0 {
+            return None;
         }
-        .map(|p| {
-            (
-                PointE {
-                    x: p.x as f32,
-                    y: p.y as f32,
-                },
-                1,
-            )
+
+        Some(Self {
+            from: PointE {
+                x: from.point.x,
+                y: from.point.y,
+            },
+            to: PointE {
+                x: target_pos.x,
+                y: target_pos.y,
+            },
+            start,
+            target,
+            reach: match target {
+                Some(_) => 0.0,
+                None => reach(from.radius, target_radius) as f64,
+            },
+            radius: from.radius,
+            nav,
         })
     }
-    fn get_target_points(&self) -> Option<TargetVertices> {
+
+    fn center(&self, face: &FaceId) -> PointE {
         use spade::Triangulation;
 
-        let res = self
-            .store
+        let center = self.nav.triangulation.cdt.face(*face).center();
+        PointE {
+            x: center.x as f32,
+            y: center.y as f32,
+        }
+    }
+
+    /// Point the path goes through when crossing `face`, the agent position for the start face.
+    fn waypoint(&self, face: &FaceId) -> PointE {
+        match face == &self.start {
+            true => self.from,
+            false => self.center(face),
+        }
+    }
+
+    /// Point of `face` where an agent walking from its center towards the target first gets
+    /// within reach of it, for targets standing on a wall.
+    fn reach_point(&self, face: &FaceId) -> PointE {
+        use spade::{Point2, Triangulation};
+
+        let center = self.center(face);
+        let to = Point2::new(self.to.x as f64, self.to.y as f64);
+        let distance_2 = |p: Point2<f64>| (p.x - to.x).powi(2) + (p.y - to.y).powi(2);
+        let nearest = self
             .nav
             .triangulation
             .cdt
-            .locate(spade::Point2::new(self.to.x as f64, self.to.y as f64));
+            .face(*face)
+            .adjacent_edges()
+            .into_iter()
+            .map(|edge| edge.nearest_point(to))
+            .min_by(|a, b| distance_2(*a).total_cmp(&distance_2(*b)));
+        let Some(nearest) = nearest else {
+            return center;
+        };
+
+        // first intersection of the segment from the center to the nearest point of the face
+        // with the circle of reach, the whole segment lies in the face
+        let (cx, cy) = (center.x as f64, center.y as f64);
+        let (d, f) = ((nearest.x - cx, nearest.y - cy), (cx - to.x, cy - to.y));
+        let qa = d.0 * d.0 + d.1 * d.1;
+        let qb = 2.0 * (f.0 * d.0 + f.1 * d.1);
+        let qc = f.0 * f.0 + f.1 * f.1 - self.reach * self.reach;
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if qc <= 0.0 || qa <= 0.0 || discriminant < 0.0 {
+            return center;
+        }
+        let t = ((-qb - discriminant.sqrt()) / (2.0 * qa)).clamp(0.0, 1.0);
+        PointE {
+            x: (cx + d.0 * t) as f32,
+            y: (cy + d.1 * t) as f32,
+        }
+    }
 
-        let target_face = match res {
-            spade::PositionInTriangulation::OnFace(face) => {
-                self.store.nav.triangulation.cdt.face(face)
+    /// Channel of faces from the start face to the target, along with the path through their
+    /// centers.
+    fn path(&self) -> Option<PathResult> {
+        let (nodes, cost) = self.search()?;
+
+        let channel = nodes
+            .into_iter()
+            .filter_map(|node| match node {
+                SearchNode::Face(face) => Some(face),
+                SearchNode::Target => None,
+            })
+            .collect::<Vec<_>>();
+
+        let result = std::iter::once(self.from)
+            .chain(channel.iter().skip(1).map(|face| self.center(face)))
+            .collect();
+
+        // units standing on walls are reached at the edge of their reach, from the closest face
+        let target = match self.target {
+            Some(_) => self.to,
+            None => self.reach_point(channel.last()?),
+        };
+
+        Some(PathResult {
+            result,
+            target,
+            cost: (cost as f64 / COST_PRECISION) as f32,
+            channel,
+            radius: self.radius,
+        })
+    }
+
+    /// Distance left to be within reach of the target from `face`.
+    fn distance_left(&self, face: &FaceId) -> f64 {
+        (self.waypoint(face).distance_f64(self.to) - self.reach).max(0.0)
+    }
+
+    /// Faces ending the search: the face under the target if it is walkable, otherwise the faces
+    /// within reach of a unit standing at the target.
+    fn is_target(&self, face: &FaceId) -> bool {
+        use spade::{Point2, Triangulation};
+
+        match self.target {
+            Some(target) => face == &target,
+            None => {
+                let to = Point2::new(self.to.x as f64, self.to.y as f64);
+                self.nav.triangulation.cdt.face(*face).distance_2(to) <= self.reach * self.reach
             }
-            _ => return None,
+        }
+    }
+
+    /// Travel costs are rounded up so that the sum of the costs never underestimates a path.
+    fn get_neighbours(&self, node: &SearchNode) -> impl Iterator<Item = (SearchNode, i64)> + '_ {
+        let cost = |distance: f64| (distance * COST_PRECISION).ceil() as i64;
+        let face = match node {
+            SearchNode::Face(face) => Some(*face),
+            SearchNode::Target => None,
         };
 
-        Some(
-            target_face
-                .vertices()
-                .into_iter()
-                .flat_map(|v| v.as_voronoi_face().adjacent_edges())
-                .flat_map(|e| e.as_undirected().vertices())
-                .flat_map(|v| v.out_edges().unwrap().into_iter())
-                .flat_map(|edge| edge.as_undirected().vertices())
-                .map(|v| v.as_delaunay_face().unwrap())
-                .collect::<HashSet<_>>(),
+        face.into_iter().flat_map(move |face| {
+            let from = self.waypoint(&face);
+            let last_leg = self
+                .is_target(&face)
+                .then(|| (SearchNode::Target, cost(self.distance_left(&face))));
+            self.nav
+                .walkable_neighbours(face, self.radius)
+                .map(move |(next, _)| {
+                    let distance = from.distance_f64(self.waypoint(&next));
+                    (SearchNode::Face(next), cost(distance))
+                })
+                .chain(last_leg)
+        })
+    }
+
+    /// Straight line distance left, rounded down so that it never overestimates the remaining
+    /// cost.
+    fn heuristic(&self, node: &SearchNode) -> i64 {
+        match node {
+            SearchNode::Face(face) => (self.distance_left(face) * COST_PRECISION).floor() as i64,
+            SearchNode::Target => 0,
+        }
+    }
+
+    /// Shortest sequence of nodes from the start face to the target along with its cost, the
+    /// target is a node of its own so that the last leg is part of the cost.
+    fn search(&self) -> Option<(Vec<SearchNode>, i64)> {
+        pathfinding::prelude::astar(
+            &SearchNode::Face(self.start),
+            |node| self.get_neighbours(node),
+            |node| self.heuristic(node),
+            |node| node == &SearchNode::Target,
         )
     }
 }
 
+impl PointE {
+    fn distance(self, other: PointE) -> f32 {
+        ((self.x - other.x) * (self.x - other.x) + (self.y - other.y) * (self.y - other.y)).sqrt()
+    }
+
+    /// Distance computed in double precision, so that the search costs and heuristic obey the
+    /// triangle inequality once rounded.
+    fn distance_f64(self, other: PointE) -> f64 {
+        let (dx, dy) = (
+            self.x as f64 - other.x as f64,
+            self.y as f64 - other.y as f64,
+        );
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
 pub struct PathResult {
     pub result: Vec<PointE>,
+    /// End of the path, the objective itself or the closest face to it when it is not walkable.
+    pub target: PointE,
+    /// Length of the A* path going through the face centers.
+    pub cost: f32,
+    channel: Vec<FaceId>,
+    radius: f32,
 }
 
 impl PathResult {
+    /// Shortest path through the channel of navmesh faces crossed by the A* result, keeping the
+    /// agent radius away from wall corners.
     pub fn smooth_path(&self, store: &EntityStore) -> impl Iterator<Item = PointE> {
         use spade::Point2;
 
-        let mut result = self.result.iter();
-
-        let Some(init) = result.next().cloned() else {return Box::new(std::iter::empty()) as Box<dyn Iterator<Item = PointE>> };
-
-        let (result, last, _) = result.fold(
-            (vec![init], init, vec![]),
-            |(mut ps, init, mut ignored), p| {
-                let prev = ps
-                    .last()
-                    .map(|p| Point2::new(p.x as f64, p.y as f64))
-                    .unwrap();
-                let next = Point2::new(p.x as f64, p.y as f64);
-
-                if store
-                    .nav
-                    .triangulation
-                    .cdt
-                    .intersects_constraint(prev, next)
-                {
-                    ps.extend(
-                        ignored
-                            .drain(..)
-                            .array_chunks()
-                            .filter_map(|chunk: [PointE; 5]| {
-                                let centroid = PointE {
-                                    x: chunk.iter().map(|a| a.x).sum::<f32>()
-                                        / (chunk.len() as f32),
-                                    y: chunk.iter().map(|a| a.y).sum::<f32>()
-                                        / (chunk.len() as f32),
-                                };
-                                chunk.into_iter().max_by(|a, b| {
-                                    ((a.x - centroid.x).exp2() + (a.y - centroid.y).exp2())
-                                        .total_cmp(
-                                            &((b.x - centroid.x).exp2()
-                                                + (b.y - centroid.y).exp2()),
-                                        )
-                                })
-                            })
-                            .filter(|p| {
-                                let xy = Point2::new(p.x as f64, p.y as f64);
-
-                                !store.nav.triangulation.cdt.intersects_constraint(prev, xy)
-                                    && !store.nav.triangulation.cdt.intersects_constraint(xy, next)
-                                    && (((p.x as f64) - next.x).exp2()
-                                        + ((p.y as f64) - next.y).exp2()
-                                        + ((p.x as f64) - prev.x).exp2()
-                                        + ((p.y as f64) - prev.y).exp2()
-                                        < 1.5 * ((prev.x as f64) - next.x).exp2()
-                                            + ((prev.y as f64) - next.y).exp2())
-                            }),
-                    );
-                    ps.push(init);
-                } else {
-                    ignored.push(p.clone());
-                }
+        let Some(from) = self.result.first() else {
+            return Vec::new().into_iter();
+        };
 
-                (ps, p.clone(), ignored)
-            },
-        );
+        store
+            .nav
+            .string_pull(
+                Point2::new(from.x as f64, from.y as f64),
+                Point2::new(self.target.x as f64, self.target.y as f64),
+                &self.channel,
+                self.radius,
+            )
+            .into_iter()
+            .map(|p| PointE {
+                x: p.x as f32,
+                y: p.y as f32,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
 
-        Box::new(result.into_iter().chain(Some(last)))
+    /// Length of the smoothed path.
+    pub fn length(&self, store: &EntityStore) -> f32 {
+        self.smooth_path(store)
+            .collect::<Vec<_>>()
+            .array_windows()
+            .map(|[a, b]| a.distance(*b))
+            .sum()
     }
-}
 
-pub fn compute_path(from: Point, target: &Objective, store: &EntityStore) -> Option<PathResult> {
-    use spade::{Point2, Triangulation};
+    /// Length of the smoothed path until it comes within `distance` of `point`.
+    pub fn length_until(&self, store: &EntityStore, point: Point, distance: f32) -> f32 {
+        let path = self.smooth_path(store).collect::<Vec<_>>();
+        let point = PointE {
+            x: point.x,
+            y: point.y,
+        };
+
+        let mut length = 0.0;
+        for [a, b] in path.array_windows() {
+            if a.distance(point) <= distance {
+                return length;
+            }
 
-    let target_pos = match target {
-        Objective::Unit(id) => {
-            store
-                .position
-                .get(store.get_raw_by_id(*id).unwrap().position)
-                .unwrap()
-                .1
-                .point
+            // first intersection of the segment with the circle around `point`
+            let (d, f) = ((b.x - a.x, b.y - a.y), (a.x - point.x, a.y - point.y));
+            let qa = d.0 * d.0 + d.1 * d.1;
+            let qb = 2.0 * (f.0 * d.0 + f.1 * d.1);
+            let qc = f.0 * f.0 + f.1 * f.1 - distance * distance;
+            let discriminant = qb * qb - 4.0 * qa * qc;
+            if qa > 0.0 && discriminant >= 0.0 {
+                let t = (-qb - discriminant.sqrt()) / (2.0 * qa);
+                if (0.0..=1.0).contains(&t) {
+                    return length + t * qa.sqrt();
+                }
+            }
+            length += a.distance(*b);
         }
-        Objective::Position(p) => p.clone(),
+        length
     }
-    .cast::<i64>();
 
-    let instance = PathfindInstance {
-        from: PointE {
-            x: from.x as f32,
-            y: from.y as f32,
-        },
-        to: PointE {
-            x: target_pos.x as f32,
-            y: target_pos.y as f32,
-        },
-        store,
-    };
+    /// Polyline following the smoothed path.
+    pub fn to_path(&self, store: &EntityStore) -> lyon::path::Path {
+        polyline(self.smooth_path(store).map(|p| Point::new(p.x, p.y)))
+    }
+}
 
-    let target_points = instance.get_target_points()?;
+/// Distance between the centers of an agent of `radius` and a unit of `target_radius` at which
+/// the unit is reached. Positions have no extent and are reached by standing on them.
+pub fn reach(radius: f32, target_radius: f32) -> f32 {
+    match target_radius > 0.0 {
+        true => target_radius + radius,
+        false => 0.0,
+    }
+}
 
-    let result = pathfinding::prelude::astar(
-        &instance.from,
-        |pos| instance.get_neighbours(pos),
-        |v| {
-            let h = |v: &PointE| -> i64 {
-                ((v.x - instance.to.x) * (v.x - instance.to.x)
-                    + (v.y - instance.to.y) * (v.y - instance.to.y)) as i64
-            };
-            /*
-            let h = |v: &Point2D<i64, UnknownUnit>| -> i64 {
-                (v.x - target_pos.x).abs() + (v.y - target_pos.y).abs()
-            };
-             */
-            h(v)
-        },
-        |v| match store
-            .nav
-            .triangulation
-            .cdt
-            .locate(Point2::new(v.x as f64, v.y as f64))
-        {
-            spade::PositionInTriangulation::OnFace(face) => {
-                target_points.contains(&store.nav.triangulation.cdt.face(face))
-            }
-            _ => false,
-        },
-    )?
-    .0;
+/// Path for an agent occupying `from` to reach `target`, crossing only edges wide enough for
+/// its radius.
+pub fn compute_path(
+    from: &PositionComponent,
+    target: &Objective,
+    store: &EntityStore,
+) -> Option<PathResult> {
+    navmesh_path(from, target.position(store)?, &store.nav)
+}
 
-    return Some(PathResult { result });
+/// Path for an agent occupying `from` to reach a unit of the given radius standing at `target`.
+fn navmesh_path(
+    from: &PositionComponent,
+    target: (Point, f32),
+    nav: &NavigationMap,
+) -> Option<PathResult> {
+    PathfindInstance::new(from, target, nav)?.path()
 }
 
 pub struct PathfindingComponent {
@@ -321,6 +590,14 @@ pub enum PathfindError {
     EndReached(lyon::math::Point),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TravelError {
+    /// No path leads to the objective.
+    Unreachable,
+    /// The unit cannot move.
+    Immobile,
+}
+
 impl PathfindingComponent {
     pub fn add_objective(&mut self, objective: Objective) {
         self.objectives.push_front(objective)
@@ -330,6 +607,10 @@ impl PathfindingComponent {
         matches!(self.path, Pathfinding::Static)
     }
 
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.path, Pathfinding::Dynamic { .. })
+    }
+
     pub fn no_path() -> Self {
         Self {
             path: Pathfinding::Static,
@@ -348,6 +629,30 @@ impl PathfindingComponent {
         }
     }
 
+    pub fn dynamic(path: lyon::path::Path, speed: f32) -> Self {
+        let mut component = Self {
+            speed,
+            ..Self::no_path()
+        };
+        component.follow(path);
+        component
+    }
+
+    /// Start following `path` from its beginning, keeping the queued objectives. Returns the
+    /// end of the path.
+    pub fn follow(&mut self, path: lyon::path::Path) -> Option<Point> {
+        let start = path.first_endpoint()?.0;
+        let end = path.last_endpoint()?.0;
+        self.path = Pathfinding::Dynamic {
+            lengths: ArcLengths::new(&path),
+            path,
+            start,
+            end,
+        };
+        self.position = 0.0;
+        Some(end)
+    }
+
     pub fn offset_position(mut self, offset: f32) -> Self {
         self.position += offset;
         self
@@ -359,7 +664,322 @@ pub enum Pathfinding {
     Persistent(Arc<lyon::path::Path>),
     Dynamic {
         path: lyon::path::Path,
-        start: GameTimer,
-        end: GameTimer,
+        lengths: ArcLengths,
+        start: Point,
+        end: Point,
     },
 }
+
+#[test]
+fn smooth_path_avoids_constraints() {
+    use spade::{Point2, Triangulation};
+
+    let (_, store) = crate::MinimapEngine::init();
+    let mut points = crate::nav_engine::random_points(0x5eed).filter(|p| store.nav.is_walkable(*p));
+
+    let mut found = 0;
+    for _ in 0..64 {
+        let (from, to) = (points.next().unwrap(), points.next().unwrap());
+        let agent = PositionComponent {
+            point: from,
+            radius: 0.0,
+        };
+        let Some(result) = compute_path(&agent, &Objective::Position(to), &store) else {
+            continue;
+        };
+        found += 1;
+
+        let path = result.smooth_path(&store).collect::<Vec<_>>();
+        assert!(path.len() >= 2);
+        assert_eq!(Point::new(path[0].x, path[0].y), from);
+        assert!(path.last() == Some(&result.target));
+
+        // the pulled string only bends around the corners of the channel it goes through
+        let corners = result
+            .channel
+            .iter()
+            .flat_map(|face| store.nav.triangulation.cdt.face(*face).vertices())
+            .map(|vertex| vertex.position())
+            .collect::<Vec<_>>();
+        let is_corner = |p: &PointE| {
+            let (x, y) = (p.x as f64, p.y as f64);
+            corners
+                .iter()
+                .any(|c| (c.x - x).abs() < 0.01 && (c.y - y).abs() < 0.01)
+        };
+        for bend in &path[1..path.len() - 1] {
+            assert!(
+                is_corner(bend),
+                "{from:?} -> {to:?}: bends at {:?} away from any corner",
+                (bend.x, bend.y)
+            );
+        }
+
+        // and is never longer than the polyline through the centers of the channel faces
+        let centers = result
+            .result
+            .iter()
+            .chain(Some(&result.target))
+            .collect::<Vec<_>>();
+        let through_centers = centers
+            .array_windows()
+            .map(|[a, b]| a.distance(**b))
+            .sum::<f32>();
+        assert!(result.length(&store) <= through_centers + 0.5);
+
+        for [a, b] in path.array_windows() {
+            assert!(
+                !store.nav.triangulation.cdt.intersects_constraint(
+                    Point2::new(a.x as f64, a.y as f64),
+                    Point2::new(b.x as f64, b.y as f64)
+                ),
+                "{from:?} -> {to:?}: segment {:?} -> {:?} crosses a wall",
+                (a.x, a.y),
+                (b.x, b.y)
+            );
+        }
+    }
+    assert!(found > 0);
+}
+
+#[test]
+fn radius_path_keeps_clear_of_walls() {
+    const RADIUS: f32 = 65.0;
+
+    let (_, store) = crate::MinimapEngine::init();
+    let mut points = crate::nav_engine::random_points(0x5eed).filter(|p| {
+        store.nav.is_walkable(*p)
+            && store.nav.wall_distance([p.x as f64, p.y as f64]) > RADIUS as f64
+    });
+
+    let mut found = 0;
+    for _ in 0..32 {
+        let (from, to) = (points.next().unwrap(), points.next().unwrap());
+        let agent = PositionComponent {
+            point: from,
+            radius: RADIUS,
+        };
+        let Some(result) = compute_path(&agent, &Objective::Position(to), &store) else {
+            continue;
+        };
+        found += 1;
+
+        // every edge of the channel is wide enough for the agent
+        for [a, b] in result.channel.array_windows() {
+            assert!(
+                store
+                    .nav
+                    .walkable_neighbours(*a, RADIUS)
+                    .any(|(next, _)| next == *b),
+                "{from:?} -> {to:?}: the channel crosses an edge narrower than the agent"
+            );
+        }
+
+        // so a point going the same way never needs a longer path
+        let point = PositionComponent {
+            point: from,
+            radius: 0.0,
+        };
+        let narrow = compute_path(&point, &Objective::Position(to), &store).unwrap();
+        assert!(narrow.length(&store) <= result.length(&store) + 0.5);
+
+        let path = result.smooth_path(&store).collect::<Vec<_>>();
+        for [a, b] in path.array_windows() {
+            for i in 0..=16 {
+                let t = i as f32 / 16.0;
+                let p = [
+                    (a.x + (b.x - a.x) * t) as f64,
+                    (a.y + (b.y - a.y) * t) as f64,
+                ];
+                let clearance = store.nav.wall_distance(p);
+                assert!(
+                    clearance >= RADIUS as f64 - 2.0,
+                    "{from:?} -> {to:?}: {p:?} is {clearance} away from a wall"
+                );
+            }
+        }
+    }
+    assert!(found > 0);
+}
+
+#[test]
+fn point_paths_do_not_cross_walls() {
+    use spade::Triangulation;
+
+    let (_, store) = crate::MinimapEngine::init();
+    let nav = &store.nav;
+    let cdt = &nav.triangulation.cdt;
+
+    // no edge given to an agent without a radius is a wall
+    for face in cdt.inner_faces() {
+        let face = face.fix();
+        if nav.triangulation.excluded.contains(&face) {
+            continue;
+        }
+        for (next, edge) in nav.walkable_neighbours(face, 0.0) {
+            assert!(!cdt.directed_edge(edge).is_constraint_edge());
+            assert!(!nav.triangulation.excluded.contains(&next));
+        }
+    }
+
+    // so paths of point agents go from face to face without crossing one
+    let mut points = crate::nav_engine::random_points(0x0ba1).filter(|p| nav.is_walkable(*p));
+    let mut found = 0;
+    for _ in 0..32 {
+        let (from, to) = (points.next().unwrap(), points.next().unwrap());
+        let agent = PositionComponent {
+            point: from,
+            radius: 0.0,
+        };
+        let Some(result) = compute_path(&agent, &Objective::Position(to), &store) else {
+            continue;
+        };
+        found += 1;
+        for [a, b] in result.channel.array_windows() {
+            let edge = nav
+                .walkable_neighbours(*a, 0.0)
+                .find_map(|(next, edge)| (next == *b).then_some(edge));
+            assert!(
+                edge.is_some(),
+                "{from:?} -> {to:?}: the channel jumps between faces"
+            );
+        }
+    }
+    assert!(found > 0);
+}
+
+#[test]
+fn path_length_is_bounded_by_straight_line() {
+    let (_, store) = crate::MinimapEngine::init();
+    let mut points = crate::nav_engine::random_points(0x1e57).filter(|p| store.nav.is_walkable(*p));
+
+    let mut found = 0;
+    for _ in 0..128 {
+        let (from, to) = (points.next().unwrap(), points.next().unwrap());
+        let agent = PositionComponent {
+            point: from,
+            radius: 0.0,
+        };
+        let Some(result) = compute_path(&agent, &Objective::Position(to), &store) else {
+            continue;
+        };
+        found += 1;
+
+        let straight = (to - from).length();
+        let length = result.length(&store);
+        assert!(
+            straight - 0.5 <= length && length <= result.cost + 0.5,
+            "{from:?} -> {to:?}: straight line {straight}, path {length}, cost {}",
+            result.cost
+        );
+
+        // the heuristic must not make the search miss a shorter path
+        let instance = PathfindInstance::new(&agent, (to, 0.0), &store.nav).unwrap();
+        let (nodes, astar) = instance.search().unwrap();
+        assert!(instance.heuristic(&nodes[0]) <= astar);
+
+        // the heuristic is consistent, it drops by at most the cost of each step
+        for [a, b] in nodes.array_windows() {
+            let (_, cost) = instance
+                .get_neighbours(a)
+                .find(|(next, _)| next == b)
+                .unwrap();
+            assert!(
+                instance.heuristic(a) <= cost + instance.heuristic(b),
+                "{from:?} -> {to:?}"
+            );
+        }
+
+        let (_, dijkstra) = pathfinding::prelude::dijkstra(
+            &SearchNode::Face(instance.start),
+            |node| instance.get_neighbours(node),
+            |node| node == &SearchNode::Target,
+        )
+        .unwrap();
+        assert_eq!(astar, dijkstra, "{from:?} -> {to:?}");
+    }
+    assert!(found > 0);
+}
+
+#[test]
+fn arc_lengths_match_path_walk() {
+    let (_, store) = crate::MinimapEngine::init();
+
+    for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+        let path = &store.lanes[(Team::Blue, lane)];
+        let lengths = store.lanes.lengths(path).unwrap();
+
+        let length = lyon::algorithms::length::approximate_length(path.iter(), 0.1);
+        assert!((lengths.length() - length).abs() < 1.0);
+
+        let mut walked = vec![];
+        let mut pattern = lyon::algorithms::walk::RegularPattern {
+            callback: &mut |event: lyon::algorithms::walk::WalkerEvent| {
+                walked.push((event.distance, event.position));
+                true
+            },
+            interval: 250.0,
+        };
+        lyon::algorithms::walk::walk_along_path(path.iter(), 0.0, 0.1, &mut pattern);
+
+        assert!(!walked.is_empty());
+        for (distance, expected) in walked {
+            let point = lengths.point_at(distance).unwrap();
+            assert!(
+                (point - expected).length() < 1.0,
+                "{lane:?} at {distance}: {point:?} != {expected:?}"
+            );
+
+            // points of the path project back to where they are along it
+            let along = lengths.project(expected).unwrap();
+            assert!(
+                (along - distance).abs() < 1.0,
+                "{lane:?} at {distance}: {along}"
+            );
+        }
+    }
+}
+
+#[test]
+fn lane_paths_avoid_walls() {
+    use spade::Point2;
+
+    let (_, store) = crate::MinimapEngine::init();
+    for team in [Team::Blue, Team::Red] {
+        for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+            let lengths = store.lanes.lengths(&store.lanes[(team, lane)]).unwrap();
+
+            let samples = (0..=(lengths.length() / 25.0) as usize)
+                .map(|i| lengths.point_at(i as f32 * 25.0).unwrap())
+                .chain(lengths.point_at(lengths.length()))
+                .map(|p| Point2::new(p.x as f64, p.y as f64))
+                .collect::<Vec<_>>();
+            for [a, b] in samples.array_windows() {
+                assert!(
+                    !store.nav.triangulation.cdt.intersects_constraint(*a, *b),
+                    "{team:?} {lane:?}: {a:?} -> {b:?} crosses a wall"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn every_store_derives_its_own_lane_paths() {
+    let (_, store) = crate::MinimapEngine::init();
+    let (_, other) = crate::MinimapEngine::init();
+
+    for team in [Team::Blue, Team::Red] {
+        for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+            let (path, other_path) = (&store.lanes[(team, lane)], &other.lanes[(team, lane)]);
+            assert!(!Arc::ptr_eq(path, other_path));
+            assert_eq!(
+                store.lanes.lengths(path).unwrap().length(),
+                other.lanes.lengths(other_path).unwrap().length()
+            );
+
+            // paths of another store are measured like any other path
+            assert!(store.lanes.lengths(other_path).is_none());
+        }
+    }
+}
@@ -30,6 +30,8 @@ impl UnitId {
     const NEXUS_BOT_TURRET: u64 = 5;
     const INHIBITOR: u64 = 6;
     const NEXUS: u64 = 7;
+    const CAMP: u64 = 8;
+    const MONSTER: u64 = 32;
 
     pub fn new(team: Option<Team>, lane: Option<Lane>) -> Self {
         // 0000     0000    0000    0000  16..32  0000 0000 0000 0000
@@ -85,6 +87,12 @@ impl UnitId {
         id <= Self::NEXUS_BOT_TURRET
     }
 
+    /// Turrets, inhibitors and nexuses.
+    pub fn is_structure(&self) -> bool {
+        let id = self.0 >> 32;
+        (Self::OUTER_TURRET..=Self::NEXUS).contains(&id)
+    }
+
     pub fn team(&self) -> Option<Team> {
         let masked = self.0 & 0b1111;
         match masked {
@@ -93,6 +101,17 @@ impl UnitId {
             _ => None,
         }
     }
+
+    pub fn lane(&self) -> Option<Lane> {
+        let masked = (self.0 >> 4) & 0b1111;
+        match masked {
+            1 => Some(Lane::Top),
+            2 => Some(Lane::Mid),
+            3 => Some(Lane::Bot),
+            4 => Some(Lane::Nexus),
+            _ => None,
+        }
+    }
 }
 
 impl From<crate::ecs::structures::turret::TurretIndex> for UnitId {
@@ -142,6 +161,24 @@ impl From<&crate::ecs::structures::nexus::NexusIndex> for UnitId {
     }
 }
 
+impl From<crate::ecs::units::camp::CampIndex> for UnitId {
+    fn from(value: crate::ecs::units::camp::CampIndex) -> Self {
+        let (mut id, _) = Self::from_tl(None, None);
+        let offset = 32;
+        id |= (Self::CAMP + value.code()) << offset;
+        Self(id)
+    }
+}
+
+impl From<crate::ecs::units::monster::MonsterIndex> for UnitId {
+    fn from(value: crate::ecs::units::monster::MonsterIndex) -> Self {
+        let (mut id, _) = Self::from_tl(None, None);
+        let offset = 32;
+        id |= (Self::MONSTER + value.0 as u64) << offset;
+        Self(id)
+    }
+}
+
 #[test]
 fn gen_id() {
     dbg!(UnitId::new(None, None));
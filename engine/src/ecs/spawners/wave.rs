@@ -11,10 +11,12 @@ pub struct WaveBuilder {
     base_pos: f32,
     lane: Option<Lane>,
     team: Option<Team>,
+    path: Option<std::sync::Arc<lyon::path::Path>>,
     melee: usize,
     siege: bool,
     ranged: usize,
     superm: usize,
+    spawn: GameTimer,
 }
 
 impl WaveBuilder {
@@ -28,6 +30,17 @@ impl WaveBuilder {
         self
     }
 
+    /// Lane path walked by the minions of the wave.
+    pub fn set_path(mut self, path: std::sync::Arc<lyon::path::Path>) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    pub fn set_spawn(mut self, spawn: GameTimer) -> Self {
+        self.spawn = spawn;
+        self
+    }
+
     pub fn has_siege(mut self, siege: bool) -> Self {
         self.siege = siege;
         self
@@ -38,11 +51,12 @@ impl WaveBuilder {
         inhibs: [crate::ecs::structures::inhibitor::Inhibitor<'_>; 3],
     ) -> Self {
         let Some(lane) = self.lane else { return self };
+        let spawn = self.spawn;
 
-        if inhibs[lane as usize].is_down() {
+        if inhibs[lane as usize].is_down_at(spawn) {
             self.superm = 1;
         }
-        if inhibs.iter().fold(true, |a, inh| a && inh.is_down()) {
+        if inhibs.iter().all(|inh| inh.is_down_at(spawn)) {
             self.superm = 2;
         }
         if self.superm > 0 {
@@ -58,10 +72,12 @@ impl Default for WaveBuilder {
             base_pos: 0.0,
             team: None,
             lane: None,
+            path: None,
             melee: 3,
             siege: false,
             ranged: 3,
             superm: 0,
+            spawn: GameTimer::GAME_START,
         }
     }
 }
@@ -92,7 +108,9 @@ impl EntitySpawner for WaveBuilder {
             minion
                 .set_lane(self.lane.expect("no lane for spawner"))
                 .set_team(self.team.expect("no team for spawner"))
+                .set_path(self.path.clone().expect("no path for spawner"))
                 .set_offset(self.base_pos)
+                .set_spawn(self.spawn)
         });
 
         self.base_pos -= 100.0
@@ -109,6 +127,12 @@ pub fn wave_number(spawn: GameTimer) -> usize {
     ((spawn - GameTimer::FIRST_SPAWN).as_secs() / GameTimer::WAVE_PERIOD.as_secs() + 1) as usize
 }
 
+/// Spawn time of the `wave`th wave of the game, waves are numbered from 1.
+pub fn wave_spawn(wave: usize) -> GameTimer {
+    debug_assert!(wave > 0);
+    GameTimer::FIRST_SPAWN + GameTimer(GameTimer::WAVE_PERIOD.0 * wave.saturating_sub(1) as u32)
+}
+
 pub fn has_siege(spawn: GameTimer) -> bool {
     let wn = wave_number(spawn);
 
@@ -122,9 +146,14 @@ pub fn has_siege(spawn: GameTimer) -> bool {
 
 pub fn timer_to_wave_spawn(from: GameTimer, to: GameTimer) -> impl Iterator<Item = GameTimer> {
     debug_assert!(from <= to);
-    let from = std::cmp::max(from, GameTimer::FIRST_SPAWN);
-    (from.as_secs()..to.as_secs())
-        .step_by(GameTimer::WAVE_PERIOD.as_secs() as usize)
-        .filter(|a| (a - GameTimer::FIRST_SPAWN.as_secs()) % GameTimer::WAVE_PERIOD.as_secs() == 0)
+    let first = GameTimer::FIRST_SPAWN.as_secs();
+    let period = GameTimer::WAVE_PERIOD.as_secs();
+    // waves spawn on whole seconds, round both ends up so that sub-second timers are kept
+    let ceil = |timer: GameTimer| timer.as_secs() + u64::from(timer.subsec_nanos() > 0);
+    let from = ceil(std::cmp::max(from, GameTimer::FIRST_SPAWN));
+    // first spawn at or after `from`, which isn't always aligned on the wave period
+    let from = from + (period - (from - first) % period) % period;
+    (from..ceil(to))
+        .step_by(period as usize)
         .map(|s| GameTimer(std::time::Duration::from_secs(s)))
 }
@@ -1,22 +1,36 @@
 use std::{collections::HashMap, ptr::NonNull};
 
 use crate::{
+    core::{GameTimer, Lane, Team},
     ecs::{
-        entity::{Entity, EntityBuilder, SpecificComponent, SpecificComponentBuilder},
-        generic::{pathfinding::PathfindingComponent, PositionComponent},
+        entity::{
+            Entity, EntityBuilder, EntityRef, SpecificComponent, SpecificComponentBuilder, UnitMut,
+        },
+        generic::{
+            pathfinding::{
+                compute_path, reach, LanePaths, Objective, PathfindingComponent, TravelError,
+            },
+            PositionComponent,
+        },
         UnitId,
     },
     nav_engine::{CollisionBox, NavigationMap},
+    stats::{Damage, GoldCollectable, WithUnitStats},
     structures::{
-        inhibitor::{Inhibitor, InhibitorComponent},
-        turret::{Turret, TurretComponent},
+        inhibitor::{Inhibitor, InhibitorComponent, InhibitorIndex},
+        turret::{Turret, TurretComponent, TurretIndex, TurretKind},
+    },
+    units::{
+        camp::{Camp, CampComponent, CampIndex},
+        champion::{Champion, ChampionComponent, ChampionMut},
+        minion::{Minion, MinionComponent, MinionMut},
+        monster::{DragonKind, DragonSequence, Monster, MonsterComponent, MonsterIndex, MonsterKind},
     },
-    units::minion::{Minion, MinionComponent, MinionMut},
 };
 
 use super::{
     entity::UnitRemoval,
-    structures::nexus::{Nexus, NexusIndex},
+    structures::nexus::{Nexus, NexusComponent, NexusIndex},
 };
 
 type WithId<T> = (UnitId, T);
@@ -28,8 +42,18 @@ pub struct EntityStore {
 
     pub(crate) turrets: slab::Slab<WithId<TurretComponent>>,
     pub(crate) inhibitors: slab::Slab<WithId<InhibitorComponent>>,
+    pub(crate) nexuses: slab::Slab<WithId<NexusComponent>>,
     pub(crate) minions: slab::Slab<WithId<MinionComponent>>,
+    pub(crate) camps: slab::Slab<WithId<CampComponent>>,
+    pub(crate) monsters: slab::Slab<WithId<MonsterComponent>>,
+    pub(crate) champions: slab::Slab<WithId<ChampionComponent>>,
+    /// Gold earned by each unit.
+    pub(crate) gold: HashMap<UnitId, f32>,
+    /// Experience earned by each unit.
+    pub(crate) experience: HashMap<UnitId, f32>,
     pub nav: NavigationMap,
+    /// Paths followed by the minions of each lane.
+    pub lanes: LanePaths,
 }
 
 impl EntityStore {
@@ -46,9 +70,21 @@ impl EntityStore {
             SpecificComponentBuilder::Inhibitor(inhib) => {
                 SpecificComponent::Inhibitor(self.inhibitors.insert((guid, inhib)))
             }
+            SpecificComponentBuilder::Nexus(nexus) => {
+                SpecificComponent::Nexus(self.nexuses.insert((guid, nexus)))
+            }
             SpecificComponentBuilder::Minion(minion) => {
                 SpecificComponent::Minion(self.minions.insert((guid, minion)))
             }
+            SpecificComponentBuilder::Camp(camp) => {
+                SpecificComponent::Camp(self.camps.insert((guid, camp)))
+            }
+            SpecificComponentBuilder::Monster(monster) => {
+                SpecificComponent::Monster(self.monsters.insert((guid, monster)))
+            }
+            SpecificComponentBuilder::Champion(champion) => {
+                SpecificComponent::Champion(self.champions.insert((guid, champion)))
+            }
         };
 
         let components = Entity {
@@ -93,6 +129,15 @@ impl EntityStore {
             })
     }
 
+    pub fn get_unit_mut(&mut self, id: impl Into<UnitId>) -> Option<UnitMut<'_>> {
+        self.get_raw_by_id_mut(id.into())
+            .map(|entity| unsafe { std::ptr::NonNull::new_unchecked(entity) })
+            .map(|entity| UnitMut {
+                store: self,
+                entity,
+            })
+    }
+
     pub fn get_minion(&self, id: impl Into<UnitId>) -> Option<Minion<'_>> {
         self.get_raw_by_id(id.into()).and_then(|entity| {
             entity.is_minion().then_some(Minion {
@@ -148,11 +193,31 @@ impl EntityStore {
                     .try_remove(key)
                     .ok_or(format!("{}:{}", file!(), line!()))?;
             }
+            SpecificComponent::Nexus(key) => {
+                self.nexuses
+                    .try_remove(key)
+                    .ok_or(format!("{}:{}", file!(), line!()))?;
+            }
             SpecificComponent::Minion(key) => {
                 self.minions
                     .try_remove(key)
                     .ok_or(format!("{}:{}", file!(), line!()))?;
             }
+            SpecificComponent::Camp(key) => {
+                self.camps
+                    .try_remove(key)
+                    .ok_or(format!("{}:{}", file!(), line!()))?;
+            }
+            SpecificComponent::Monster(key) => {
+                self.monsters
+                    .try_remove(key)
+                    .ok_or(format!("{}:{}", file!(), line!()))?;
+            }
+            SpecificComponent::Champion(key) => {
+                self.champions
+                    .try_remove(key)
+                    .ok_or(format!("{}:{}", file!(), line!()))?;
+            }
         }
 
         Ok(entity.guid)
@@ -179,18 +244,310 @@ impl EntityStore {
             .flatten()
     }
 
-    pub fn get_nexus(&self, team: crate::core::Team) -> Option<Nexus<'_>> {
-        self.get_raw_by_id(NexusIndex::from(team).guid())
-            .map(|entity| Nexus {
+    pub fn get_camp(&self, id: impl Into<UnitId>) -> Option<Camp<'_>> {
+        self.get_raw_by_id(id.into()).and_then(|entity| {
+            entity.is_camp().then_some(Camp {
                 store: self,
                 entity,
             })
+        })
+    }
+
+    pub fn camps(&self) -> impl Iterator<Item = Camp<'_>> {
+        self.camps
+            .iter()
+            .filter_map(|(_, (id, _))| self.get_camp(*id))
+    }
+
+    pub fn get_monster(&self, id: impl Into<UnitId>) -> Option<Monster<'_>> {
+        self.get_raw_by_id(id.into()).and_then(|entity| {
+            entity.is_monster().then_some(Monster {
+                store: self,
+                entity,
+            })
+        })
+    }
+
+    pub fn monsters(&self) -> impl Iterator<Item = Monster<'_>> {
+        self.monsters
+            .iter()
+            .filter_map(|(_, (id, _))| self.get_monster(*id))
+    }
+
+    pub fn get_champion(&self, id: impl Into<UnitId>) -> Option<Champion<'_>> {
+        self.get_raw_by_id(id.into()).and_then(|entity| {
+            entity.is_champion().then_some(Champion {
+                store: self,
+                entity,
+            })
+        })
+    }
+
+    pub fn get_champion_mut(&mut self, id: impl Into<UnitId>) -> Option<ChampionMut<'_>> {
+        self.get_raw_by_id_mut(id.into())
+            .and_then(|entity| {
+                entity
+                    .is_champion()
+                    .then_some(unsafe { std::ptr::NonNull::new_unchecked(entity) })
+            })
+            .map(|entity| ChampionMut {
+                store: self,
+                entity,
+            })
+    }
+
+    pub fn champions(&self) -> impl Iterator<Item = Champion<'_>> {
+        self.champions
+            .iter()
+            .filter_map(|(_, (id, _))| self.get_champion(*id))
+    }
+
+    pub fn get_nexus(&self, team: crate::core::Team) -> Option<Nexus<'_>> {
+        self.get_raw_by_id(NexusIndex::from(team).guid())
+            .and_then(|entity| {
+                entity.is_nexus().then_some(Nexus {
+                    store: self,
+                    entity,
+                })
+            })
     }
 
     pub fn nexuses(&self) -> impl Iterator<Item = Nexus<'_>> {
         self.get_nexus(crate::core::Team::Blue).into_iter().chain(self.get_nexus(crate::core::Team::Red))
     }
 
+    /// Remove health from the unit `guid`, minions are removed and turrets fall when they have no
+    /// health left. Structures that can't be attacked yet at `timer` take no damage.
+    pub(crate) fn deal_damage(&mut self, guid: UnitId, damage: Damage, timer: GameTimer) {
+        let Some(entity) = self.get_raw_by_id(guid) else {
+            return;
+        };
+
+        match entity.specific {
+            SpecificComponent::Minion(key) => {
+                let minion = &mut self.minions[key].1;
+                minion.health -= damage.against(&minion.base_stats());
+                if minion.health <= 0.0 {
+                    // champions around the dying minion share its experience
+                    let experience = minion.experience();
+                    let champions = self
+                        .get_minion(guid)
+                        .map(|minion| minion.champions_in_xp_range())
+                        .unwrap_or_default();
+                    self.share_experience(&champions, experience);
+                    self.remove_by_id(guid).expect("can't delete minion");
+                }
+            }
+            SpecificComponent::Turret(key) => {
+                if !self.get_turret(guid).is_some_and(|turret| turret.is_vulnerable(timer)) {
+                    return;
+                }
+
+                // champions around the turret when the plates fall share their gold
+                let champions = self
+                    .get_turret(guid)
+                    .map(|turret| turret.champions_in_range())
+                    .unwrap_or_default();
+
+                let turret = &mut self.turrets[key].1;
+                let fallen = turret.lose_health(damage.against(&turret.base_stats()));
+                self.share_gold(&champions, (fallen * TurretComponent::PLATE_GOLD) as f32);
+            }
+            SpecificComponent::Nexus(key) => {
+                let vulnerable = guid
+                    .team()
+                    .and_then(|team| self.get_nexus(team))
+                    .is_some_and(|nexus| nexus.is_vulnerable());
+                if vulnerable {
+                    let nexus = &mut self.nexuses[key].1;
+                    nexus.health = (nexus.health - damage.against(&nexus.base_stats())).max(0.0);
+                }
+            }
+            SpecificComponent::Inhibitor(_)
+            | SpecificComponent::Camp(_)
+            | SpecificComponent::Monster(_)
+            | SpecificComponent::Champion(_)
+            | SpecificComponent::None => {}
+        }
+    }
+
+    /// Team who destroyed the enemy nexus, the game is over once there is one.
+    pub fn winner(&self) -> Option<Team> {
+        self.nexuses()
+            .find(|nexus| !nexus.is_up())
+            .and_then(|nexus| nexus.team())
+            .map(|team| team.opposite())
+    }
+
+    /// Split `gold` evenly between `units`.
+    pub(crate) fn share_gold(&mut self, units: &[UnitId], gold: f32) {
+        if units.is_empty() || gold <= 0.0 {
+            return;
+        }
+        for unit in units {
+            *self.gold.entry(*unit).or_default() += gold / units.len() as f32;
+        }
+    }
+
+    /// Split `experience` evenly between `units`.
+    pub(crate) fn share_experience(&mut self, units: &[UnitId], experience: f32) {
+        if units.is_empty() || experience <= 0.0 {
+            return;
+        }
+        for unit in units {
+            *self.experience.entry(*unit).or_default() += experience / units.len() as f32;
+        }
+    }
+
+    /// Experience earned by `guid` so far.
+    pub fn experience(&self, guid: UnitId) -> f32 {
+        self.experience.get(&guid).copied().unwrap_or_default()
+    }
+
+    /// Gold earned by `guid` so far.
+    pub fn gold(&self, guid: UnitId) -> f32 {
+        self.gold.get(&guid).copied().unwrap_or_default()
+    }
+
+    /// Gold left on the plates of the outer turret of `team` in `lane`.
+    pub fn plate_gold(&self, team: Team, lane: Lane) -> usize {
+        self.get_turret(TurretIndex(team, lane, TurretKind::Outer))
+            .map(|turret| turret.plates() * TurretComponent::PLATE_GOLD)
+            .unwrap_or_default()
+    }
+
+    /// Destroy the inhibitor `index` at `timer`, it respawns `GameTimer::INHIBITOR_RESPAWN` later.
+    pub fn destroy_inhibitor(&mut self, index: InhibitorIndex, timer: GameTimer) {
+        let Some(SpecificComponent::Inhibitor(key)) =
+            self.get_raw_by_id(index.into()).map(|entity| entity.specific.clone())
+        else {
+            return;
+        };
+        self.inhibitors[key].1.down = Some(timer);
+    }
+
+    /// Kill the camp `index` at `timer`, it spawns again after its respawn timer.
+    pub fn kill_camp(&mut self, index: CampIndex, timer: GameTimer) {
+        let Some(SpecificComponent::Camp(key)) =
+            self.get_raw_by_id(index.into()).map(|entity| entity.specific.clone())
+        else {
+            return;
+        };
+        let camp = &mut self.camps[key].1;
+        camp.next_spawn = Some(timer + camp.kind.respawn());
+    }
+
+    /// Kill the epic monster `kind` at `timer`, the kill counts for `team`.
+    pub fn kill_monster(&mut self, kind: MonsterKind, team: Team, timer: GameTimer) {
+        let Some(SpecificComponent::Monster(key)) = self
+            .get_raw_by_id(MonsterIndex(kind).into())
+            .map(|entity| entity.specific.clone())
+        else {
+            return;
+        };
+        self.monsters[key].1.kill(team, timer);
+    }
+
+    /// Replace the order in which the elemental dragons spawn.
+    pub fn set_dragons(&mut self, dragons: DragonSequence) {
+        for (_, (_, monster)) in self.monsters.iter_mut() {
+            if monster.kind == MonsterKind::Dragon {
+                monster.dragons = Some(dragons.clone());
+            }
+        }
+    }
+
+    /// Epic monsters waiting to spawn, the soonest first.
+    pub fn upcoming_monsters(&self) -> Vec<(MonsterKind, GameTimer)> {
+        let mut upcoming = self
+            .monsters
+            .iter()
+            .filter_map(|(_, (_, monster))| Some((monster.kind, monster.next_spawn()?)))
+            .collect::<Vec<_>>();
+        upcoming.sort_by_key(|(_, timer)| *timer);
+        upcoming
+    }
+
+    /// Next epic monster to spawn and when.
+    pub fn next_monster(&self) -> Option<(MonsterKind, GameTimer)> {
+        self.upcoming_monsters().into_iter().next()
+    }
+
+    /// Kind of the next dragon to spawn, or of the one alive in the pit.
+    pub fn next_dragon(&self) -> Option<DragonKind> {
+        self.get_monster(MonsterIndex::DRAGON)?
+            .get_state()
+            .dragons()
+            .map(|dragons| dragons.next())
+    }
+
+    /// Turrets protecting `victim` from `attacker` shoot at the attacker, as when a champion
+    /// attacks an enemy champion under their turret.
+    pub fn call_for_help(&mut self, attacker: UnitId, victim: UnitId) {
+        let turrets = self
+            .turrets()
+            .filter(|turret| turret.is_up() && turret.team() == victim.team())
+            .filter(|turret| turret.reaches(attacker) && turret.reaches(victim))
+            .map(|turret| turret.guid())
+            .collect::<Vec<_>>();
+
+        for (_, (guid, turret)) in self.turrets.iter_mut() {
+            if turrets.contains(guid) {
+                turret.aggro = Some(attacker);
+            }
+        }
+    }
+
+    /// Time for a unit of `radius` walking at `movespeed` from `from` to reach `to`. Units are
+    /// reached at their edge rather than their center.
+    pub fn travel_time(
+        &self,
+        from: lyon::math::Point,
+        to: &Objective,
+        movespeed: f32,
+        radius: f32,
+    ) -> Result<GameTimer, TravelError> {
+        if movespeed <= 0.0 {
+            return Err(TravelError::Immobile);
+        }
+
+        let agent = PositionComponent {
+            point: from,
+            radius,
+        };
+        let (target, target_radius) = to.position(self).ok_or(TravelError::Unreachable)?;
+        let path = compute_path(&agent, to, self).ok_or(TravelError::Unreachable)?;
+        let distance = path.length_until(self, target, reach(radius, target_radius));
+
+        Ok(GameTimer(std::time::Duration::from_secs_f32(
+            distance / movespeed,
+        )))
+    }
+
+    /// Navmesh region a unit of `radius` walking at `movespeed` from `from` can reach within
+    /// `budget`.
+    pub fn isochrone(
+        &self,
+        from: lyon::math::Point,
+        budget: GameTimer,
+        movespeed: f32,
+        radius: f32,
+    ) -> geo::MultiPolygon<f64> {
+        self.nav.isochrone(
+            spade::Point2::new(from.x as f64, from.y as f64),
+            budget.as_secs_f64() * movespeed as f64,
+            radius,
+        )
+    }
+
+    /// Every entity owning a pathfinding component, minions included.
+    pub fn units_mut(&mut self) -> impl Iterator<Item = UnitMut<'_>> {
+        let mut storeref = unsafe { NonNull::new_unchecked(self) };
+        self.pathfinding
+            .iter()
+            .filter_map(move |(_, (id, _))| unsafe { storeref.as_mut() }.get_unit_mut(*id))
+    }
+
     pub fn minions_mut(&mut self) -> impl Iterator<Item = MinionMut<'_>> {
         let mut storeref = unsafe { NonNull::new_unchecked(self) };
         self.minions
@@ -199,3 +556,78 @@ impl EntityStore {
             .flatten()
     }
 }
+
+#[test]
+fn travel_time_to_unit_edge() {
+    use crate::{
+        ecs::entity::EntityBuilder,
+        structures::turret::TurretIndex,
+        units::champion::{ChampionBuilder, ChampionStats, Role},
+    };
+
+    const MOVESPEED: f32 = 345.0;
+
+    let (_, mut store) = crate::MinimapEngine::init();
+    let turret = TurretIndex::BLUE_MID_OUTER.guid();
+    let center = store
+        .position
+        .get(store.get_raw_by_id(turret).unwrap().position)
+        .unwrap()
+        .1;
+
+    // open ground in front of the turret
+    let from = center.point + lyon::math::vector(-1000.0, 1000.0);
+    let halfway = center.point + lyon::math::vector(-500.0, 500.0);
+
+    let to_halfway = store
+        .travel_time(from, &Objective::Position(halfway), MOVESPEED, 0.0)
+        .unwrap();
+    let expected = (halfway - from).length() / MOVESPEED;
+    assert!(
+        (to_halfway.as_secs_f32() - expected).abs() < 0.01,
+        "{to_halfway:?} {expected}"
+    );
+
+    let to_turret = store
+        .travel_time(from, &Objective::Unit(turret), MOVESPEED, 0.0)
+        .unwrap();
+    // the turret is reached at its edge, no sooner than in a straight line across open ground
+    let to_edge = ((center.point - from).length() - center.radius) / MOVESPEED;
+    let to_turret = to_turret.as_secs_f32();
+    assert!(
+        to_edge - 0.01 <= to_turret && to_turret <= to_edge + 0.05,
+        "{to_turret} {to_edge}"
+    );
+
+    // units on open ground are reached as soon as the edges of both units touch
+    let stats = ChampionStats::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/champions.json"))
+        .expect("champions data file should load");
+    let champion = store.spawn(
+        ChampionBuilder::new("Lee Sin", stats["Lee Sin"])
+            .set_team(Team::Blue)
+            .set_role(Role::Mid)
+            .set_position(halfway),
+    );
+    let to_champion = store
+        .travel_time(
+            from,
+            &Objective::Unit(champion),
+            MOVESPEED,
+            ChampionBuilder::RADIUS,
+        )
+        .unwrap();
+    let expected = ((halfway - from).length() - 2.0 * ChampionBuilder::RADIUS) / MOVESPEED;
+    assert!(
+        (to_champion.as_secs_f32() - expected).abs() < 0.01,
+        "{to_champion:?} {expected}"
+    );
+
+    assert_eq!(
+        store.travel_time(from, &Objective::Unit(turret), 0.0, 0.0),
+        Err(TravelError::Immobile)
+    );
+    assert_eq!(
+        store.travel_time(from, &Objective::Unit(UnitId::null()), MOVESPEED, 0.0),
+        Err(TravelError::Unreachable)
+    );
+}
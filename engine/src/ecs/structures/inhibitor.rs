@@ -11,7 +11,20 @@ use super::MAP_BOUNDS;
 
 #[derive(Debug, Default)]
 pub struct InhibitorComponent {
-    down: Option<GameTimer>,
+    pub(crate) down: Option<GameTimer>,
+}
+
+impl InhibitorComponent {
+    /// Time at which a destroyed inhibitor comes back up.
+    pub fn respawn_at(&self) -> Option<GameTimer> {
+        self.down.map(|down| down + GameTimer::INHIBITOR_RESPAWN)
+    }
+
+    /// Whether the inhibitor is destroyed at `timer`.
+    pub fn is_down_at(&self, timer: GameTimer) -> bool {
+        self.down
+            .is_some_and(|down| down <= timer && timer < down + GameTimer::INHIBITOR_RESPAWN)
+    }
 }
 
 pub struct Inhibitor<'a> {
@@ -34,8 +47,8 @@ impl Inhibitor<'_> {
         self.get_state().down.is_none()
     }
 
-    pub(crate) fn is_down(&self) -> bool {
-        self.get_state().down.is_some()
+    pub(crate) fn is_down_at(&self, timer: GameTimer) -> bool {
+        self.get_state().is_down_at(timer)
     }
 
     pub fn get_state(&self) -> &InhibitorComponent {
@@ -53,6 +66,15 @@ impl<'store> EntityRef<'store> for Inhibitor<'store> {
     }
 }
 
+/// Bring back up the inhibitors whose respawn timer is over at `timer`.
+pub(crate) fn respawn(store: &mut EntityStore, timer: GameTimer) {
+    for (_, (_, inhib)) in store.inhibitors.iter_mut() {
+        if inhib.respawn_at().is_some_and(|respawn| respawn <= timer) {
+            inhib.down = None;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct InhibitorIndex(pub Team, pub Lane);
 
@@ -93,3 +115,39 @@ impl EntityBuilder for InhibitorIndex {
         SpecificComponentBuilder::Inhibitor(InhibitorComponent { down: None })
     }
 }
+
+#[test]
+fn super_minions_spawn_while_inhibitor_is_down() {
+    use crate::{ecs::units::minion::MinionType, Engine};
+    use std::time::Duration;
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let at = |secs| GameTimer(Duration::from_secs(secs));
+    let super_minions = |store: &EntityStore| {
+        store
+            .minions()
+            .filter(|minion| minion.guid().team() == Some(Team::Red))
+            .filter(|minion| minion.guid().lane() == Some(Lane::Mid))
+            .filter(|minion| minion.get_state().kind == MinionType::SuperMinion)
+            .count()
+    };
+    // run a step containing the wave spawning at `spawn`, returns the super minions it added
+    let mut spawn_wave = |store: &mut EntityStore, spawn| {
+        let before = super_minions(store);
+        engine.timer = spawn - at(1);
+        engine.on_step(store, at(2));
+        super_minions(store) - before
+    };
+
+    assert_eq!(spawn_wave(&mut store, at(9 * 60 + 35)), 0);
+
+    store.destroy_inhibitor(InhibitorIndex::BLUE_MID, at(10 * 60));
+    assert!(!store.get_inhib(InhibitorIndex::BLUE_MID).unwrap().is_up());
+    assert_eq!(spawn_wave(&mut store, at(10 * 60 + 5)), 1);
+    assert_eq!(spawn_wave(&mut store, at(14 * 60 + 35)), 1);
+    assert!(!store.get_inhib(InhibitorIndex::BLUE_MID).unwrap().is_up());
+
+    // the inhibitor respawns 5 minutes after being destroyed
+    assert_eq!(spawn_wave(&mut store, at(15 * 60 + 5)), 0);
+    assert!(store.get_inhib(InhibitorIndex::BLUE_MID).unwrap().is_up());
+}
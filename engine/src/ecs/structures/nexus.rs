@@ -1,12 +1,37 @@
 use crate::{
-    core::Team,
+    core::{Lane, Team},
     ecs::{
-        entity::{EntityBuilder, SpecificComponentBuilder, EntityRef, Entity},
+        entity::{EntityBuilder, SpecificComponentBuilder, EntityRef, EntityRefCrateExt, Entity},
         generic::{pathfinding::PathfindingComponent, PositionComponent},
         UnitId, store::EntityStore,
     },
+    stats::{UnitStatistics, WithUnitStats},
 };
 
+use super::turret::{TurretIndex, TurretKind};
+
+#[derive(Debug)]
+pub struct NexusComponent {
+    pub health: f32,
+}
+
+impl Default for NexusComponent {
+    fn default() -> Self {
+        let mut component = Self { health: 0.0 };
+        component.health = component.base_stats().health;
+        component
+    }
+}
+
+impl WithUnitStats for NexusComponent {
+    fn base_stats(&self) -> UnitStatistics {
+        UnitStatistics {
+            health: 5500.0,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NexusIndex {
     pub(crate) team: Team,
@@ -37,7 +62,7 @@ impl EntityBuilder for NexusIndex {
     }
 
     fn specific(&self) -> SpecificComponentBuilder {
-        SpecificComponentBuilder::None
+        SpecificComponentBuilder::Nexus(NexusComponent::default())
     }
 }
 
@@ -53,10 +78,32 @@ impl<'a> std::fmt::Debug for Nexus<'a> {
         f.debug_struct("Nexus")
             .field("id", &self.entity.guid)
             .field("position", &self.position())
+            .field("state", self.get_state())
             .finish()
     }
 }
 
+impl Nexus<'_> {
+    pub fn get_state(&self) -> &NexusComponent {
+        &self.store.nexuses[self.get_specific_unchecked().unwrap()].1
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.get_state().health > 0.0
+    }
+
+    /// The nexus can only be attacked once both of its turrets are down.
+    pub fn is_vulnerable(&self) -> bool {
+        let Some(team) = self.team() else {
+            return false;
+        };
+        [TurretKind::NexusTop, TurretKind::NexusBot]
+            .into_iter()
+            .filter_map(|kind| self.store.get_turret(TurretIndex(team, Lane::Nexus, kind)))
+            .all(|turret| !turret.is_up())
+    }
+}
+
 impl<'store> EntityRef<'store> for Nexus<'store> {
     fn store_ref(&self) -> &'store EntityStore {
         self.store
@@ -65,4 +112,51 @@ impl<'store> EntityRef<'store> for Nexus<'store> {
     fn entity(&self) -> &Entity {
         self.entity
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn game_ends_when_the_nexus_falls() {
+    use crate::{
+        core::GameTimer, ecs::structures::inhibitor::InhibitorIndex, stats::Damage, Engine,
+    };
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let nexus = NexusIndex::from(Team::Red).guid();
+    let turrets = [TurretIndex::RED_TOP_NEXUS, TurretIndex::RED_BOT_NEXUS];
+    let health = |store: &EntityStore| store.get_nexus(Team::Red).unwrap().get_state().health;
+    let now = GameTimer::MINUTES_25;
+
+    // nexus turrets and the nexus can't be attacked while the inhibitors stand
+    for turret in turrets {
+        store.deal_damage(turret.guid(), Damage::True(10000.0), now);
+        assert!(store.get_turret(turret).unwrap().is_up());
+    }
+    store.deal_damage(nexus, Damage::True(1000.0), now);
+    assert_eq!(health(&store), 5500.0);
+
+    // an inhibitor only opens the nexus turrets once it is down
+    store.destroy_inhibitor(InhibitorIndex::RED_MID, now);
+    let before = now - GameTimer(std::time::Duration::from_secs(1));
+    store.deal_damage(turrets[0].guid(), Damage::True(10000.0), before);
+    assert!(store.get_turret(turrets[0]).unwrap().is_up());
+
+    store.deal_damage(turrets[0].guid(), Damage::True(10000.0), now);
+    assert!(!store.get_turret(turrets[0]).unwrap().is_up());
+    store.deal_damage(nexus, Damage::True(1000.0), now);
+    assert_eq!(health(&store), 5500.0);
+
+    store.deal_damage(turrets[1].guid(), Damage::True(10000.0), now);
+    store.deal_damage(nexus, Damage::True(1000.0), now);
+    assert_eq!(health(&store), 4500.0);
+    assert_eq!(store.winner(), None);
+
+    store.deal_damage(nexus, Damage::True(10000.0), now);
+    assert!(!store.get_nexus(Team::Red).unwrap().is_up());
+    assert_eq!(store.winner(), Some(Team::Blue));
+
+    // the engine stops, no waves spawn anymore
+    engine.timer = now;
+    engine.on_step(&mut store, GameTimer::WAVE_PERIOD);
+    assert_eq!(store.minions().count(), 0);
+    assert_eq!(engine.timer, now);
+}
@@ -1,19 +1,162 @@
+use std::collections::HashMap;
+
 use lyon::math::Point;
 
 use crate::{
-    core::{Lane, Team},
+    core::{GameTimer, Lane, Team},
     ecs::{
         entity::{Entity, EntityBuilder, EntityRef, EntityRefCrateExt, SpecificComponentBuilder},
         generic::{pathfinding::PathfindingComponent, PositionComponent},
         store::EntityStore,
+        units::minion::MinionType,
+        UnitId,
     },
+    stats::{Damage, UnitStatistics, WithUnitStats},
 };
 
-use super::MAP_BOUNDS;
+use super::{inhibitor::InhibitorIndex, MAP_BOUNDS};
 
 #[derive(Debug)]
 pub struct TurretComponent {
-    pub(crate) _state: TurretState,
+    pub(crate) state: TurretState,
+    pub(crate) kind: TurretKind,
+    pub health: f32,
+    /// Unit shot at during the last step.
+    pub(crate) target: Option<UnitId>,
+    /// Champion who attacked an allied champion under the turret.
+    pub(crate) aggro: Option<UnitId>,
+    /// Seconds left before the next shot can be fired.
+    pub(crate) cooldown: f32,
+}
+
+impl TurretComponent {
+    pub const PLATES: usize = 5;
+    /// Health lost by an outer turret each time one of its plates falls.
+    pub const PLATE_HEALTH: f32 = 1000.0;
+    /// Gold shared by the enemy champions around a turret when one of its plates falls.
+    pub const PLATE_GOLD: usize = 125;
+
+    fn new(kind: TurretKind, state: TurretState) -> Self {
+        let mut component = Self {
+            state,
+            kind,
+            health: 0.0,
+            target: None,
+            aggro: None,
+            cooldown: 0.0,
+        };
+        component.health = component.base_stats().health;
+        component
+    }
+
+    /// Plates left on a turret with `health`, plates fall when the health crosses a multiple of
+    /// [`Self::PLATE_HEALTH`].
+    pub fn plates_for(health: f32) -> usize {
+        ((health / Self::PLATE_HEALTH).ceil().max(0.0) as usize).min(Self::PLATES)
+    }
+
+    /// Take `health` away from the turret, returns the number of plates that fell.
+    pub(crate) fn lose_health(&mut self, health: f32) -> usize {
+        if matches!(self.state, TurretState::Down) {
+            return 0;
+        }
+        self.health = (self.health - health).max(0.0);
+
+        let fallen = match self.state {
+            TurretState::UpWithPlates { plates } => {
+                let left = Self::plates_for(self.health).min(plates);
+                self.state = TurretState::UpWithPlates { plates: left };
+                plates - left
+            }
+            _ => 0,
+        };
+        if self.health <= 0.0 {
+            self.state = TurretState::Down;
+            self.target = None;
+            self.aggro = None;
+        }
+        fallen
+    }
+
+    /// Fraction of the maximum health of a minion removed by a shot of this turret.
+    pub fn minion_damage(&self, minion: MinionType) -> f32 {
+        match (minion, self.kind) {
+            (MinionType::Melee, _) => 0.45,
+            (MinionType::Ranged, _) => 0.70,
+            (MinionType::Siege, TurretKind::Outer) => 0.14,
+            (MinionType::Siege, TurretKind::Inner) => 0.11,
+            (MinionType::Siege, _) => 0.08,
+            (MinionType::SuperMinion, _) => 0.07,
+        }
+    }
+}
+
+impl WithUnitStats for TurretComponent {
+    fn base_stats(&self) -> UnitStatistics {
+        let mut stats = UnitStatistics {
+            range: 750.0,
+            attack_speed: 0.8333,
+            ..Default::default()
+        };
+        match self.kind {
+            TurretKind::Outer => {
+                stats.health = 5000.0;
+                stats.attack_damage = 182.0;
+                stats.armor = 40.0;
+                stats.magic_resist = 40.0;
+            }
+            TurretKind::Inner => {
+                stats.health = 3600.0;
+                stats.attack_damage = 187.0;
+                stats.armor = 55.0;
+                stats.magic_resist = 55.0;
+            }
+            TurretKind::Inhib => {
+                stats.health = 3300.0;
+                stats.attack_damage = 187.0;
+                stats.armor = 70.0;
+                stats.magic_resist = 70.0;
+            }
+            TurretKind::NexusBot | TurretKind::NexusTop => {
+                stats.health = 2700.0;
+                stats.attack_damage = 165.0;
+                stats.armor = 70.0;
+                stats.magic_resist = 70.0;
+            }
+        }
+        stats
+    }
+
+    fn current_stats(&self, gs: &GameTimer) -> UnitStatistics {
+        let mut stats = self.base_stats();
+
+        match self.kind {
+            TurretKind::Outer => {
+                if gs.as_secs() > 30 {
+                    let upgrades = ((gs.as_secs_f32() - 30.0) / 60.0).floor() + 1.0;
+                    stats.attack_damage = (stats.attack_damage + (12.0 * upgrades)).min(350.0);
+                }
+            }
+            TurretKind::Inner => {
+                if gs.as_secs() > 3 * 60 {
+                    let upgrades = ((gs.as_secs_f32() - 3.0 * 60.0) / 60.0).floor() + 1.0;
+                    stats.attack_damage = (stats.attack_damage + (16.0 * upgrades)).min(427.0);
+                }
+                if gs.as_secs() > 16 * 60 {
+                    let upgrades = ((gs.as_secs_f32() - 16.0 * 60.0) / 60.0).floor() + 1.0;
+                    stats.armor = (stats.armor + upgrades).min(70.0);
+                    stats.magic_resist = (stats.magic_resist + upgrades).min(70.0);
+                }
+            }
+            TurretKind::Inhib | TurretKind::NexusBot | TurretKind::NexusTop => {
+                if gs.as_secs() > 3 * 60 {
+                    let upgrades = ((gs.as_secs_f32() - 3.0 * 60.0) / 60.0).floor() + 1.0;
+                    stats.attack_damage = (stats.attack_damage + (16.0 * upgrades)).min(427.0);
+                }
+            }
+        }
+        stats
+    }
 }
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
@@ -57,6 +200,103 @@ impl Turret<'_> {
     pub fn get_state(&self) -> &TurretComponent {
         &self.store.turrets[self.get_specific_unchecked().unwrap()].1
     }
+
+    pub fn state(&self) -> &TurretState {
+        &self.get_state().state
+    }
+
+    pub fn is_up(&self) -> bool {
+        !matches!(self.state(), TurretState::Down)
+    }
+
+    /// Nexus turrets can only be attacked once one of the inhibitors of their team is down at
+    /// `timer`.
+    pub fn is_vulnerable(&self, timer: GameTimer) -> bool {
+        if !self.get_state().kind.is_nexus() {
+            return true;
+        }
+        let Some(team) = self.team() else {
+            return true;
+        };
+        [Lane::Top, Lane::Mid, Lane::Bot].into_iter().any(|lane| {
+            self.store
+                .get_inhib(InhibitorIndex(team, lane))
+                .is_some_and(|inhib| inhib.is_down_at(timer))
+        })
+    }
+
+    pub fn plates(&self) -> usize {
+        match self.state() {
+            TurretState::UpWithPlates { plates } => *plates,
+            _ => 0,
+        }
+    }
+
+    /// Whether `guid` stands within the range of the turret, measured from edge to edge.
+    pub fn reaches(&self, guid: UnitId) -> bool {
+        self.units_in_range().any(|(unit, _)| unit == guid)
+    }
+
+    fn units_in_range(&self) -> impl Iterator<Item = (UnitId, f32)> + '_ {
+        let reach = self.get_state().base_stats().range + self.radius();
+        self.store
+            .nav
+            .units_in_range(*self.position(), reach)
+            .filter(|(guid, _)| !guid.is_structure())
+            .map(|(guid, position)| (guid, (position.point - *self.position()).length()))
+    }
+
+    fn enemies_in_range(&self) -> impl Iterator<Item = (UnitId, f32)> + '_ {
+        let team = self.team().map(|team| team.opposite());
+        self.units_in_range()
+            .filter(move |(guid, _)| guid.team().is_some() && guid.team() == team)
+    }
+
+    /// Enemy units in range that are neither minions nor structures.
+    pub fn champions_in_range(&self) -> Vec<UnitId> {
+        self.enemies_in_range()
+            .filter(|(guid, _)| self.store.get_minion(*guid).is_none())
+            .map(|(guid, _)| guid)
+            .collect()
+    }
+
+    /// Unit the turret shoots at: the champion who attacked an allied champion under it, then the
+    /// unit it was already shooting at, then the closest minion and finally the closest champion.
+    pub fn target(&self) -> Option<UnitId> {
+        if !self.is_up() {
+            return None;
+        }
+
+        let state = self.get_state();
+        let enemies = self.enemies_in_range().collect::<Vec<_>>();
+        let in_range = |guid: &UnitId| enemies.iter().any(|(enemy, _)| enemy == guid);
+        if let Some(aggro) = state.aggro.filter(in_range) {
+            return Some(aggro);
+        }
+        if let Some(target) = state.target.filter(in_range) {
+            return Some(target);
+        }
+
+        enemies
+            .iter()
+            .min_by(|(a, da), (b, db)| {
+                let minion = |guid: &UnitId| self.store.get_minion(*guid).is_some();
+                minion(b).cmp(&minion(a)).then(da.total_cmp(db))
+            })
+            .map(|(guid, _)| *guid)
+    }
+
+    /// Damage of a shot of the turret at `target`, minions lose a fixed part of their health.
+    pub fn damage(&self, target: UnitId, timer: &GameTimer) -> Damage {
+        let state = self.get_state();
+        match self.store.get_minion(target) {
+            Some(minion) => {
+                let minion = minion.get_state();
+                Damage::True(state.minion_damage(minion.kind) * minion.current_stats(timer).health)
+            }
+            None => Damage::Physical(state.current_stats(timer).attack_damage),
+        }
+    }
 }
 
 impl<'a> std::fmt::Debug for Turret<'a> {
@@ -141,11 +381,60 @@ impl EntityBuilder for TurretIndex {
     }
 
     fn specific(&self) -> SpecificComponentBuilder {
-        let _state = match self {
+        let state = match self {
             TurretIndex(_, _, TurretKind::Outer) => TurretState::UpWithPlates { plates: 5 },
             _ => TurretState::Up,
         };
-        SpecificComponentBuilder::Turret(TurretComponent { _state })
+        SpecificComponentBuilder::Turret(TurretComponent::new(self.2, state))
+    }
+}
+
+/// Remaining plates fall off the outer turrets, without giving any gold.
+pub(crate) fn remove_plates(store: &mut EntityStore) {
+    for (_, (_, turret)) in store.turrets.iter_mut() {
+        if let TurretState::UpWithPlates { .. } = turret.state {
+            turret.state = TurretState::Up;
+        }
+    }
+}
+
+/// Turrets shoot at their target for `step`, `timer` is the game time used to scale their damage.
+pub(crate) fn shoot(store: &mut EntityStore, timer: GameTimer, step: GameTimer) {
+    let targets = store
+        .turrets()
+        .filter_map(|turret| Some((turret.guid(), turret.target()?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut shots = vec![];
+    for (_, (guid, turret)) in store.turrets.iter_mut() {
+        let elapsed = step.as_secs_f32();
+        turret.target = targets.get(guid).copied();
+        if turret.aggro != turret.target {
+            // champions are only chased while they stay under the turret
+            turret.aggro = None;
+        }
+
+        let Some(target) = turret.target else {
+            turret.cooldown = (turret.cooldown - elapsed).max(0.0);
+            continue;
+        };
+
+        let attack_speed = turret.base_stats().attack_speed;
+        while turret.cooldown <= elapsed {
+            shots.push((*guid, target));
+            turret.cooldown += 1.0 / attack_speed;
+        }
+        turret.cooldown -= elapsed;
+    }
+
+    for (turret, target) in shots {
+        let Some(damage) = store
+            .get_turret(turret)
+            .map(|turret| turret.damage(target, &timer))
+        else {
+            continue;
+        };
+        store.deal_damage(target, damage, timer);
     }
 }
 
@@ -160,3 +449,126 @@ impl std::fmt::Debug for TurretKind {
         }
     }
 }
+
+#[test]
+fn turrets_shoot_minions_before_champions() {
+    use crate::{
+        ecs::entity::{EntityMut, TestChampion},
+        units::minion::MinionBuilder,
+    };
+
+    let (_, mut store) = crate::MinimapEngine::init();
+    let timer = GameTimer::FIRST_SPAWN;
+    let tick = GameTimer::COMBAT_TICK;
+    let index = TurretIndex::BLUE_MID_OUTER;
+    let center = index.position().point;
+
+    let spawn_minion = |store: &mut EntityStore| {
+        let minion = store.spawn(
+            MinionBuilder::melee()
+                .set_team(Team::Red)
+                .set_lane(Lane::Mid)
+                .set_path(std::sync::Arc::clone(&store.lanes[(Team::Red, Lane::Mid)])),
+        );
+        store
+            .get_minion_mut(minion)
+            .unwrap()
+            .move_to(center + lyon::math::vector(400.0, -400.0));
+        minion
+    };
+    let minion = spawn_minion(&mut store);
+    let champion = store.spawn(TestChampion(
+        Team::Red,
+        center + lyon::math::vector(300.0, 0.0),
+    ));
+
+    // minions are shot first and lose a fixed part of their health with each shot
+    shoot(&mut store, timer, tick);
+    let turret = store.get_turret(index).unwrap();
+    assert_eq!(turret.get_state().target, Some(minion));
+    let state = store.get_minion(minion).unwrap().get_state().clone();
+    let health = state.current_stats(&timer).health * 0.55;
+    assert!((state.health - health).abs() < 0.01, "{}", state.health);
+
+    let mut ticks = 1;
+    while store.get_minion(minion).is_some() {
+        shoot(&mut store, timer, tick);
+        ticks += 1;
+    }
+    assert!(ticks as f32 * tick.as_secs_f32() <= 3.0 / 0.8333);
+
+    // the champion is shot once no minion is left
+    shoot(&mut store, timer, tick);
+    assert_eq!(
+        store.get_turret(index).unwrap().get_state().target,
+        Some(champion)
+    );
+
+    // a champion attacking an allied champion draws the aggro away from the minions
+    let minion = spawn_minion(&mut store);
+    let ally = store.spawn(TestChampion(
+        Team::Blue,
+        center + lyon::math::vector(0.0, -300.0),
+    ));
+    shoot(&mut store, timer, tick);
+    store.call_for_help(champion, ally);
+    shoot(&mut store, timer, tick);
+    assert_eq!(
+        store.get_turret(index).unwrap().get_state().target,
+        Some(champion)
+    );
+    assert!(store.get_minion(minion).is_some());
+
+    // fallen turrets stop shooting
+    store.deal_damage(index.guid(), Damage::True(5000.0), timer);
+    shoot(&mut store, timer, tick);
+    let turret = store.get_turret(index).unwrap();
+    assert!(!turret.is_up());
+    assert_eq!(turret.get_state().target, None);
+}
+
+#[test]
+fn plates_fall_with_health_and_at_fourteen_minutes() {
+    use crate::{ecs::entity::TestChampion, Engine};
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let index = TurretIndex::BLUE_TOP_OUTER;
+    let center = index.position().point;
+    let champions = [
+        store.spawn(TestChampion(
+            Team::Red,
+            center + lyon::math::vector(300.0, 0.0),
+        )),
+        store.spawn(TestChampion(
+            Team::Red,
+            center + lyon::math::vector(0.0, 300.0),
+        )),
+    ];
+    let gold = TurretComponent::PLATE_GOLD;
+    assert_eq!(store.plate_gold(Team::Blue, Lane::Top), 5 * gold);
+
+    // a plate falls every 1000 health lost, its gold is shared between the champions around
+    store.deal_damage(index.guid(), Damage::True(1500.0), engine.timer);
+    assert_eq!(store.get_turret(index).unwrap().plates(), 4);
+    assert_eq!(store.plate_gold(Team::Blue, Lane::Top), 4 * gold);
+    for champion in champions {
+        assert_eq!(store.gold(champion), gold as f32 / 2.0);
+    }
+
+    store.deal_damage(index.guid(), Damage::True(1000.0), engine.timer);
+    assert_eq!(store.get_turret(index).unwrap().plates(), 3);
+    assert_eq!(store.gold(champions[0]), gold as f32);
+
+    // the remaining plates fall off at 14:00 without giving gold
+    engine.timer = GameTimer::MINUTES_14 - GameTimer(std::time::Duration::from_secs(1));
+    engine.on_step(&mut store, GameTimer(std::time::Duration::from_secs(2)));
+    for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+        for team in [Team::Blue, Team::Red] {
+            assert_eq!(store.plate_gold(team, lane), 0);
+        }
+    }
+    let turret = store.get_turret(index).unwrap();
+    assert!(matches!(turret.state(), TurretState::Up));
+    assert_eq!(turret.get_state().health, 2500.0);
+    assert_eq!(store.gold(champions[0]), gold as f32);
+}
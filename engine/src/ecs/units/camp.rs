@@ -0,0 +1,355 @@
+use std::time::Duration;
+
+use lyon::math::Point;
+
+use crate::{
+    core::{GameTimer, Team},
+    ecs::{
+        entity::{Entity, EntityBuilder, EntityRef, EntityRefCrateExt, SpecificComponentBuilder},
+        generic::{pathfinding::PathfindingComponent, PositionComponent},
+        store::EntityStore,
+        structures::MAP_BOUNDS,
+        UnitId,
+    },
+    jungle::CampClear,
+    stats::{GoldCollectable, UnitStatistics},
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CampKind {
+    BlueBuff,
+    RedBuff,
+    Gromp,
+    Wolves,
+    Raptors,
+    Krugs,
+    ScuttleTop,
+    ScuttleBot,
+}
+
+impl CampKind {
+    pub const ALL: [Self; 8] = [
+        Self::BlueBuff,
+        Self::RedBuff,
+        Self::Gromp,
+        Self::Wolves,
+        Self::Raptors,
+        Self::Krugs,
+        Self::ScuttleTop,
+        Self::ScuttleBot,
+    ];
+
+    /// Time at which the camp first spawns.
+    pub fn first_spawn(&self) -> GameTimer {
+        let secs = match self {
+            CampKind::BlueBuff | CampKind::RedBuff => 90,
+            CampKind::Wolves | CampKind::Raptors => 90,
+            CampKind::Gromp | CampKind::Krugs => 102,
+            CampKind::ScuttleTop | CampKind::ScuttleBot => 210,
+        };
+        GameTimer(Duration::from_secs(secs))
+    }
+
+    /// Time between the death of the camp and its next spawn.
+    pub fn respawn(&self) -> GameTimer {
+        let secs = match self {
+            CampKind::BlueBuff | CampKind::RedBuff => 300,
+            CampKind::Gromp | CampKind::Wolves | CampKind::Raptors | CampKind::Krugs => 135,
+            CampKind::ScuttleTop | CampKind::ScuttleBot => 150,
+        };
+        GameTimer(Duration::from_secs(secs))
+    }
+
+    /// Gold given by all the monsters of the camp.
+    pub fn gold(&self) -> usize {
+        match self {
+            CampKind::BlueBuff | CampKind::RedBuff => 90,
+            CampKind::Gromp => 80,
+            CampKind::Wolves => 85,
+            CampKind::Raptors => 85,
+            CampKind::Krugs => 99,
+            CampKind::ScuttleTop | CampKind::ScuttleBot => 55,
+        }
+    }
+
+    /// Experience given by all the monsters of the camp.
+    pub fn experience(&self) -> f32 {
+        match self {
+            CampKind::BlueBuff | CampKind::RedBuff => 95.0,
+            CampKind::Gromp => 120.0,
+            CampKind::Wolves => 95.0,
+            CampKind::Raptors => 95.0,
+            CampKind::Krugs => 132.0,
+            CampKind::ScuttleTop | CampKind::ScuttleBot => 100.0,
+        }
+    }
+
+    /// Statistics of the monsters of the camp when they first spawn, the largest one first.
+    pub fn monsters(&self) -> Vec<UnitStatistics> {
+        let monster = |health, attack_damage, attack_speed, armor, magic_resist| UnitStatistics {
+            health,
+            attack_damage,
+            attack_speed,
+            armor,
+            magic_resist,
+            ..Default::default()
+        };
+        match self {
+            CampKind::BlueBuff | CampKind::RedBuff => {
+                vec![monster(2300.0, 67.0, 0.493, 10.0, -15.0)]
+            }
+            CampKind::Gromp => vec![monster(2050.0, 70.0, 0.425, 0.0, -15.0)],
+            CampKind::Wolves => vec![
+                monster(1600.0, 36.0, 0.625, 10.0, 0.0),
+                monster(420.0, 16.0, 0.625, 0.0, 0.0),
+                monster(420.0, 16.0, 0.625, 0.0, 0.0),
+            ],
+            CampKind::Raptors => {
+                let mut raptors = vec![monster(250.0, 13.0, 1.0, 0.0, 0.0); 6];
+                raptors[0] = monster(1200.0, 17.0, 0.667, 30.0, 30.0);
+                raptors
+            }
+            CampKind::Krugs => vec![
+                monster(1350.0, 57.0, 0.613, 10.0, -15.0),
+                monster(535.0, 24.0, 0.613, 0.0, 0.0),
+            ],
+            // the crab flees instead of fighting back
+            CampKind::ScuttleTop | CampKind::ScuttleBot => {
+                vec![monster(1050.0, 0.0, 0.0, 42.0, -15.0)]
+            }
+        }
+    }
+
+    fn radius(&self) -> f32 {
+        match self {
+            CampKind::BlueBuff | CampKind::RedBuff | CampKind::Gromp => 130.0,
+            _ => 100.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CampComponent {
+    pub kind: CampKind,
+    /// Time at which the camp spawns again, `None` while it is alive.
+    pub(crate) next_spawn: Option<GameTimer>,
+}
+
+impl CampComponent {
+    pub fn is_up(&self) -> bool {
+        self.next_spawn.is_none()
+    }
+
+    pub fn next_spawn(&self) -> Option<GameTimer> {
+        self.next_spawn
+    }
+}
+
+impl GoldCollectable for CampComponent {
+    fn golds(&self) -> usize {
+        self.kind.gold()
+    }
+
+    fn experience(&self) -> f32 {
+        self.kind.experience()
+    }
+}
+
+pub struct Camp<'store> {
+    pub(crate) store: &'store EntityStore,
+    pub(crate) entity: &'store Entity,
+}
+
+impl Camp<'_> {
+    pub fn get_state(&self) -> &CampComponent {
+        &self.store.camps[self.get_specific_unchecked().unwrap()].1
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.get_state().is_up()
+    }
+
+    /// Time for a champion with `stats` to kill the camp and the health it loses doing so.
+    pub fn clear_by(&self, stats: &UnitStatistics, smite: bool) -> Option<CampClear> {
+        crate::jungle::clear_camp(stats, &self.get_state().kind.monsters(), smite)
+    }
+}
+
+impl<'store> EntityRef<'store> for Camp<'store> {
+    fn store_ref(&self) -> &'store EntityStore {
+        self.store
+    }
+
+    fn entity(&self) -> &Entity {
+        self.entity
+    }
+}
+
+impl<'a> std::fmt::Debug for Camp<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Camp")
+            .field("id", &self.entity.guid)
+            .field("position", &self.position())
+            .field("state", self.get_state())
+            .finish()
+    }
+}
+
+/// Jungle camp, by the side of the map it stands on. Scuttle crabs stand in the river and belong
+/// to no side.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct CampIndex(pub Option<Team>, pub CampKind);
+
+impl CampIndex {
+    pub const BLUE_BLUE_BUFF: Self = Self(Some(Team::Blue), CampKind::BlueBuff);
+    pub const BLUE_RED_BUFF: Self = Self(Some(Team::Blue), CampKind::RedBuff);
+    pub const BLUE_GROMP: Self = Self(Some(Team::Blue), CampKind::Gromp);
+    pub const BLUE_WOLVES: Self = Self(Some(Team::Blue), CampKind::Wolves);
+    pub const BLUE_RAPTORS: Self = Self(Some(Team::Blue), CampKind::Raptors);
+    pub const BLUE_KRUGS: Self = Self(Some(Team::Blue), CampKind::Krugs);
+
+    pub const RED_BLUE_BUFF: Self = Self(Some(Team::Red), CampKind::BlueBuff);
+    pub const RED_RED_BUFF: Self = Self(Some(Team::Red), CampKind::RedBuff);
+    pub const RED_GROMP: Self = Self(Some(Team::Red), CampKind::Gromp);
+    pub const RED_WOLVES: Self = Self(Some(Team::Red), CampKind::Wolves);
+    pub const RED_RAPTORS: Self = Self(Some(Team::Red), CampKind::Raptors);
+    pub const RED_KRUGS: Self = Self(Some(Team::Red), CampKind::Krugs);
+
+    pub const SCUTTLE_TOP: Self = Self(None, CampKind::ScuttleTop);
+    pub const SCUTTLE_BOT: Self = Self(None, CampKind::ScuttleBot);
+
+    pub const ALL: [Self; 14] = [
+        Self::BLUE_BLUE_BUFF,
+        Self::BLUE_RED_BUFF,
+        Self::BLUE_GROMP,
+        Self::BLUE_WOLVES,
+        Self::BLUE_RAPTORS,
+        Self::BLUE_KRUGS,
+        Self::RED_BLUE_BUFF,
+        Self::RED_RED_BUFF,
+        Self::RED_GROMP,
+        Self::RED_WOLVES,
+        Self::RED_RAPTORS,
+        Self::RED_KRUGS,
+        Self::SCUTTLE_TOP,
+        Self::SCUTTLE_BOT,
+    ];
+
+    /// Small number identifying the camp in its [`UnitId`].
+    pub(crate) fn code(&self) -> u64 {
+        let side = match self.0 {
+            None => 0,
+            Some(Team::Blue) => 1,
+            Some(Team::Red) => 2,
+        };
+        side * 8 + self.1 as u64
+    }
+}
+
+impl EntityBuilder for CampIndex {
+    fn guid(&self) -> UnitId {
+        UnitId::from(*self)
+    }
+
+    fn position(&self) -> PositionComponent {
+        let point = match *self {
+            Self::BLUE_BLUE_BUFF => Point::new(3821.0, MAP_BOUNDS.height - 8101.0),
+            Self::BLUE_RED_BUFF => Point::new(7762.0, MAP_BOUNDS.height - 4011.0),
+            Self::BLUE_GROMP => Point::new(2288.0, MAP_BOUNDS.height - 8448.0),
+            Self::BLUE_WOLVES => Point::new(3780.0, MAP_BOUNDS.height - 6443.0),
+            Self::BLUE_RAPTORS => Point::new(7101.0, MAP_BOUNDS.height - 5467.0),
+            Self::BLUE_KRUGS => Point::new(8394.0, MAP_BOUNDS.height - 2641.0),
+            Self::RED_BLUE_BUFF => Point::new(11032.0, MAP_BOUNDS.height - 6990.0),
+            Self::RED_RED_BUFF => Point::new(7101.0, MAP_BOUNDS.height - 10856.0),
+            Self::RED_GROMP => Point::new(12703.0, MAP_BOUNDS.height - 6443.0),
+            Self::RED_WOLVES => Point::new(11008.0, MAP_BOUNDS.height - 8387.0),
+            Self::RED_RAPTORS => Point::new(7862.0, MAP_BOUNDS.height - 9443.0),
+            Self::RED_KRUGS => Point::new(6317.0, MAP_BOUNDS.height - 12146.0),
+            Self::SCUTTLE_TOP => Point::new(4400.0, MAP_BOUNDS.height - 9600.0),
+            Self::SCUTTLE_BOT => Point::new(10500.0, MAP_BOUNDS.height - 5170.0),
+            _ => panic!("invalid camp index"),
+        };
+        PositionComponent {
+            point,
+            radius: self.1.radius(),
+        }
+    }
+
+    fn pathfinding(&self) -> PathfindingComponent {
+        PathfindingComponent::no_path()
+    }
+
+    fn specific(&self) -> SpecificComponentBuilder {
+        SpecificComponentBuilder::Camp(CampComponent {
+            kind: self.1,
+            next_spawn: Some(self.1.first_spawn()),
+        })
+    }
+}
+
+/// Bring back the camps whose respawn timer is over at `timer`.
+pub(crate) fn respawn(store: &mut EntityStore, timer: GameTimer) {
+    for (_, (_, camp)) in store.camps.iter_mut() {
+        if camp.next_spawn.is_some_and(|spawn| spawn <= timer) {
+            camp.next_spawn = None;
+        }
+    }
+}
+
+#[test]
+fn camps_spawn_and_respawn_on_their_timers() {
+    use crate::Engine;
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    assert_eq!(store.camps().count(), CampIndex::ALL.len());
+    assert!(store.camps().all(|camp| !camp.is_up()));
+
+    // camps stand on the side of the map they belong to
+    let blue = *store.get_nexus(Team::Blue).unwrap().position();
+    let red = *store.get_nexus(Team::Red).unwrap().position();
+    for camp in store.camps() {
+        let position = *camp.position();
+        match CampIndex::ALL
+            .iter()
+            .find(|index| index.guid() == camp.guid())
+        {
+            Some(CampIndex(Some(Team::Blue), _)) => {
+                assert!((position - blue).length() < (position - red).length())
+            }
+            Some(CampIndex(Some(Team::Red), _)) => {
+                assert!((position - red).length() < (position - blue).length())
+            }
+            Some(CampIndex(None, _)) => {}
+            None => panic!("unknown camp {:?}", camp.guid()),
+        }
+    }
+
+    let second = GameTimer(Duration::from_secs(1));
+    let is_up = |store: &EntityStore, index: CampIndex| store.get_camp(index).unwrap().is_up();
+    engine.timer = CampKind::BlueBuff.first_spawn() - second;
+    engine.on_step(&mut store, second);
+    assert!(is_up(&store, CampIndex::BLUE_RED_BUFF));
+    assert!(is_up(&store, CampIndex::RED_WOLVES));
+    assert!(!is_up(&store, CampIndex::BLUE_KRUGS));
+    assert!(!is_up(&store, CampIndex::SCUTTLE_TOP));
+
+    engine.timer = CampKind::ScuttleTop.first_spawn() - second;
+    engine.on_step(&mut store, second);
+    assert!(store.camps().all(|camp| camp.is_up()));
+
+    // a killed camp comes back after its respawn timer
+    let killed = engine.timer;
+    store.kill_camp(CampIndex::BLUE_RED_BUFF, killed);
+    let camp = store.get_camp(CampIndex::BLUE_RED_BUFF).unwrap();
+    assert!(!camp.is_up());
+    assert_eq!(
+        camp.get_state().next_spawn(),
+        Some(killed + CampKind::RedBuff.respawn())
+    );
+
+    engine.timer = killed + CampKind::RedBuff.respawn() - second - second;
+    engine.on_step(&mut store, second);
+    assert!(!is_up(&store, CampIndex::BLUE_RED_BUFF));
+    engine.on_step(&mut store, second);
+    assert!(is_up(&store, CampIndex::BLUE_RED_BUFF));
+}
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use lyon::math::Point;
+
+use crate::{
+    core::{GameTimer, Lane, Team},
+    ecs::{
+        entity::{
+            Entity, EntityBuilder, EntityMut, EntityRef, EntityRefCrateExt,
+            SpecificComponentBuilder,
+        },
+        generic::{pathfinding::PathfindingComponent, PositionComponent},
+        store::EntityStore,
+        UnitId,
+    },
+    stats::{UnitStatistics, WithUnitStats},
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Role {
+    Top,
+    Jungle,
+    Mid,
+    Bot,
+    Support,
+}
+
+impl Role {
+    /// Lane the role plays in, junglers have none.
+    pub fn lane(&self) -> Option<Lane> {
+        match self {
+            Role::Top => Some(Lane::Top),
+            Role::Mid => Some(Lane::Mid),
+            Role::Bot | Role::Support => Some(Lane::Bot),
+            Role::Jungle => None,
+        }
+    }
+}
+
+/// Statistics of a champion at level 1 and what it gains on each level up, as found in the
+/// champions data file. The attack speed growth is a percentage of the base attack speed.
+#[derive(Debug, Default, Clone, Copy, serde::Deserialize)]
+pub struct ChampionStats {
+    pub base: UnitStatistics,
+    #[serde(default)]
+    pub growth: UnitStatistics,
+}
+
+impl ChampionStats {
+    /// Load the statistics of every champion of a data file, by champion name.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<HashMap<String, Self>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// Statistics of the champion at `level`.
+    pub fn at_level(&self, level: usize) -> UnitStatistics {
+        // growth is not linear, later levels give slightly more
+        let n = level.saturating_sub(1) as f32;
+        let factor = n * (0.7025 + 0.0175 * n);
+        let (base, growth) = (&self.base, &self.growth);
+        UnitStatistics {
+            health: base.health + growth.health * factor,
+            health_regen: base.health_regen + growth.health_regen * factor,
+            mana: base.mana + growth.mana * factor,
+            mana_regen: base.mana_regen + growth.mana_regen * factor,
+            armor: base.armor + growth.armor * factor,
+            magic_resist: base.magic_resist + growth.magic_resist * factor,
+            attack_damage: base.attack_damage + growth.attack_damage * factor,
+            attack_speed: base.attack_speed * (1.0 + growth.attack_speed / 100.0 * factor),
+            ..*base
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChampionComponent {
+    pub name: String,
+    pub role: Role,
+    pub(crate) level: usize,
+    pub movespeed: f32,
+    pub(crate) stats: ChampionStats,
+}
+
+impl ChampionComponent {
+    pub const MAX_LEVEL: usize = 18;
+    /// Total experience needed to reach each level, from level 1 on.
+    pub const LEVEL_EXPERIENCE: [f32; Self::MAX_LEVEL] = [
+        0.0, 280.0, 660.0, 1140.0, 1720.0, 2400.0, 3180.0, 4060.0, 5040.0, 6120.0, 7300.0, 8580.0,
+        9960.0, 11440.0, 13020.0, 14700.0, 16480.0, 18360.0,
+    ];
+
+    /// Level of a champion who earned `experience`.
+    pub fn level_at(experience: f32) -> usize {
+        Self::LEVEL_EXPERIENCE.partition_point(|needed| *needed <= experience)
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn stats(&self) -> &ChampionStats {
+        &self.stats
+    }
+}
+
+impl WithUnitStats for ChampionComponent {
+    fn base_stats(&self) -> UnitStatistics {
+        UnitStatistics {
+            movespeed: self.movespeed,
+            ..self.stats.at_level(self.level)
+        }
+    }
+}
+
+pub struct Champion<'store> {
+    pub(crate) store: &'store EntityStore,
+    pub(crate) entity: &'store Entity,
+}
+
+impl Champion<'_> {
+    pub fn get_state(&self) -> &ChampionComponent {
+        &self.store.champions[self.get_specific_unchecked().unwrap()].1
+    }
+
+    pub fn current_stats(&self, timer: &GameTimer) -> UnitStatistics {
+        self.get_state().current_stats(timer)
+    }
+}
+
+impl<'store> EntityRef<'store> for Champion<'store> {
+    fn store_ref(&self) -> &'store EntityStore {
+        self.store
+    }
+
+    fn entity(&self) -> &Entity {
+        self.entity
+    }
+}
+
+impl<'a> std::fmt::Debug for Champion<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Champion")
+            .field("id", &self.entity.guid)
+            .field("position", &self.position())
+            .field("state", self.get_state())
+            .finish()
+    }
+}
+
+pub struct ChampionMut<'store> {
+    pub(crate) store: &'store mut EntityStore,
+    pub(crate) entity: std::ptr::NonNull<Entity>,
+}
+
+impl ChampionMut<'_> {
+    pub fn get_state(&self) -> &ChampionComponent {
+        &self.store.champions[self.get_specific_unchecked().unwrap()].1
+    }
+
+    pub(crate) fn get_state_mut(&mut self) -> &mut ChampionComponent {
+        let key = self.get_specific_unchecked().unwrap();
+        &mut self.store.champions[key].1
+    }
+
+    /// Set the level of the champion, between 1 and [`ChampionComponent::MAX_LEVEL`].
+    pub fn set_level(&mut self, level: usize) {
+        self.get_state_mut().level = level.clamp(1, ChampionComponent::MAX_LEVEL);
+    }
+
+    /// Set the movespeed of the champion, used from its next step on.
+    pub fn set_movespeed(&mut self, movespeed: f32) {
+        use crate::ecs::entity::EntityMutCrateExt;
+
+        self.get_state_mut().movespeed = movespeed;
+        self.pathfinding_component_mut().speed = movespeed;
+    }
+}
+
+impl<'store> EntityRef<'store> for ChampionMut<'store> {
+    fn store_ref(&self) -> &'store EntityStore {
+        unsafe { &*(self.store as *const _) }
+    }
+    fn entity(&self) -> &'store Entity {
+        unsafe { self.entity.as_ref() }
+    }
+}
+
+impl<'store> EntityMut<'store> for ChampionMut<'store> {
+    fn store_mut(&self) -> &'store mut EntityStore {
+        unsafe { &mut *(self.store as *const _ as *mut _) }
+    }
+}
+
+impl<'a> std::fmt::Debug for ChampionMut<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Champion")
+            .field("id", &self.entity().guid)
+            .field("position", &self.position())
+            .field("state", self.get_state())
+            .finish()
+    }
+}
+
+pub struct ChampionBuilder {
+    name: String,
+    stats: ChampionStats,
+    team: Option<Team>,
+    role: Option<Role>,
+    level: usize,
+    movespeed: Option<f32>,
+    position: Option<Point>,
+}
+
+impl ChampionBuilder {
+    /// Radius of the champions, the same for all of them.
+    pub const RADIUS: f32 = 65.0;
+
+    pub fn new(name: impl Into<String>, stats: ChampionStats) -> Self {
+        Self {
+            name: name.into(),
+            stats,
+            team: None,
+            role: None,
+            level: 1,
+            movespeed: None,
+            position: None,
+        }
+    }
+
+    pub fn set_team(mut self, team: Team) -> Self {
+        self.team = Some(team);
+        self
+    }
+
+    pub fn set_role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    pub fn set_level(mut self, level: usize) -> Self {
+        self.level = level.clamp(1, ChampionComponent::MAX_LEVEL);
+        self
+    }
+
+    /// Override the base movespeed of the champion, e.g. to account for boots.
+    pub fn set_movespeed(mut self, movespeed: f32) -> Self {
+        self.movespeed = Some(movespeed);
+        self
+    }
+
+    pub fn set_position(mut self, position: Point) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    fn team(&self) -> Team {
+        self.team.expect("champion team was not set")
+    }
+
+    fn role(&self) -> Role {
+        self.role.expect("champion role was not set")
+    }
+
+    fn movespeed(&self) -> f32 {
+        self.movespeed.unwrap_or(self.stats.base.movespeed)
+    }
+}
+
+impl EntityBuilder for ChampionBuilder {
+    fn guid(&self) -> UnitId {
+        UnitId::new(Some(self.team()), self.role().lane())
+    }
+
+    fn position(&self) -> PositionComponent {
+        PositionComponent {
+            point: self.position.expect("champion position was not set"),
+            radius: Self::RADIUS,
+        }
+    }
+
+    fn pathfinding(&self) -> PathfindingComponent {
+        // champions stand still until they are given an objective or a path
+        PathfindingComponent {
+            speed: self.movespeed(),
+            ..PathfindingComponent::no_path()
+        }
+    }
+
+    fn specific(&self) -> SpecificComponentBuilder {
+        SpecificComponentBuilder::Champion(ChampionComponent {
+            name: self.name.clone(),
+            role: self.role(),
+            level: self.level,
+            movespeed: self.movespeed(),
+            stats: self.stats,
+        })
+    }
+}
+
+#[test]
+fn champions_level_up_and_walk_to_their_objectives() {
+    use crate::{
+        ecs::{entity::EntityMut, generic::pathfinding::Objective},
+        units::camp::CampIndex,
+        Engine,
+    };
+
+    let stats = ChampionStats::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/champions.json"))
+        .expect("champions data file should load");
+    let lee = stats["Lee Sin"];
+
+    // stats grow with each level, attack speed by a percentage of its base value
+    let level = |level| {
+        ChampionComponent {
+            name: "Lee Sin".to_string(),
+            role: Role::Jungle,
+            level,
+            movespeed: lee.base.movespeed,
+            stats: lee,
+        }
+        .base_stats()
+    };
+    assert!((level(1).health - lee.base.health).abs() < 1e-2);
+    assert!((level(18).health - (lee.base.health + 17.0 * lee.growth.health)).abs() < 1e-2);
+    assert!(level(2).attack_damage > level(1).attack_damage);
+    assert!(
+        (level(18).attack_speed
+            - lee.base.attack_speed * (1.0 + 17.0 * lee.growth.attack_speed / 100.0))
+            .abs()
+            < 1e-4
+    );
+
+    // the jungler stands at its raptors, right after clearing them
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let raptors = *store.get_camp(CampIndex::BLUE_RAPTORS).unwrap().position();
+    let id = store.spawn(
+        ChampionBuilder::new("Lee Sin", lee)
+            .set_team(Team::Blue)
+            .set_role(Role::Jungle)
+            .set_movespeed(lee.base.movespeed + 25.0)
+            .set_position(raptors),
+    );
+    let champion = store.get_champion(id).unwrap();
+    assert_eq!(champion.team(), Some(Team::Blue));
+    assert_eq!(champion.guid().lane(), None);
+    assert_eq!(champion.get_state().level(), 1);
+    assert_eq!(store.champions().count(), 1);
+    assert!(store.get_champion(CampIndex::BLUE_RAPTORS).is_none());
+
+    let mut champion = store.get_champion_mut(id).unwrap();
+    champion.set_level(25);
+    assert_eq!(champion.get_state().level(), ChampionComponent::MAX_LEVEL);
+
+    // the champion walks to the camp given as objective at its movespeed
+    let movespeed = champion.get_state().movespeed;
+    let objective = Objective::Unit(CampIndex::BLUE_RED_BUFF.into());
+    champion.add_objective(objective.clone());
+    let from = *champion.position();
+    let travel = store
+        .travel_time(from, &objective, movespeed, ChampionBuilder::RADIUS)
+        .unwrap();
+
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    engine.on_step(&mut store, second);
+    let walked = (*store.get_champion(id).unwrap().position() - from).length();
+    assert!(
+        walked > 0.0 && walked <= movespeed + 1.0,
+        "{walked} {movespeed}"
+    );
+
+    engine.on_step(&mut store, travel);
+    let red_buff = store.get_camp(CampIndex::BLUE_RED_BUFF).unwrap();
+    let left = (*store.get_champion(id).unwrap().position() - *red_buff.position()).length();
+    assert!(
+        left <= red_buff.radius() + ChampionBuilder::RADIUS + 1.0,
+        "{left}"
+    );
+}
@@ -1,17 +1,18 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::{
-    core::{Lane, Team},
+    core::{GameTimer, Lane, Team},
     ecs::{
         self,
         entity::{
-            Entity, EntityBuilder, EntityMut, EntityRef, EntityRefCrateExt,
+            Entity, EntityBuilder, EntityMut, EntityMutCrateExt, EntityRef, EntityRefCrateExt,
             SpecificComponentBuilder,
         },
-        generic::{
-            pathfinding::{PathfindingComponent, LANE_PATHS},
-            PositionComponent,
-        },
+        generic::{pathfinding::PathfindingComponent, PositionComponent},
         store::EntityStore,
+        UnitId,
     },
+    stats::{Damage, GoldCollectable, UnitStatistics, WithUnitStats},
 };
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -22,9 +23,179 @@ pub enum MinionType {
     SuperMinion,
 }
 
+impl MinionType {
+    /// Gold given by a minion of a wave that spawned with `upgrades`, siege minions give 3 more
+    /// gold with each upgrade while super minions give a flat bounty.
+    pub fn gold(&self, upgrades: usize) -> usize {
+        match self {
+            MinionType::Melee => MinionComponent::MELEE_GOLD,
+            MinionType::Ranged => MinionComponent::RANGED_GOLD,
+            MinionType::Siege => std::cmp::min(MinionComponent::BIG_GOLD + (3 * upgrades), 90),
+            MinionType::SuperMinion => MinionComponent::SUPER_GOLD,
+        }
+    }
+
+    /// Gold given by a minion of the wave spawning at `spawn`.
+    pub fn gold_at(&self, spawn: GameTimer) -> usize {
+        self.gold(MinionComponent::upgrades_at(spawn))
+    }
+
+    /// Experience shared between the enemy champions around the minion when it dies.
+    pub fn experience(&self) -> f32 {
+        match self {
+            MinionType::Melee => 60.45,
+            MinionType::Ranged => 29.76,
+            MinionType::Siege => 93.0,
+            MinionType::SuperMinion => 97.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MinionComponent {
     pub kind: MinionType,
+    pub health: f32,
+    /// Stat upgrades the minion spawned with, one every 3 waves.
+    pub upgrades: usize,
+    /// Seconds left before the next attack can be thrown.
+    pub(crate) cooldown: f32,
+}
+
+impl MinionComponent {
+    pub const MELEE_GOLD: usize = 21;
+    pub const RANGED_GOLD: usize = 14;
+    pub const BIG_GOLD: usize = 57;
+    /// Flat bounty of super minions, <https://leagueoflegends.fandom.com/wiki/Super_minion>.
+    pub const SUPER_GOLD: usize = 60;
+    /// Upgrades giving the early stat growth, the following ones give the late growth.
+    pub const EARLY_UPGRADES: usize = 5;
+    /// Minion stats stop growing after this many upgrades.
+    pub const MAX_UPGRADES: usize = 25;
+    /// Enemy champions within this distance of a dying minion share its experience.
+    pub const XP_SHARE_RADIUS: f32 = 1600.0;
+
+    /// Movespeed bonus given to every minion at 10, 15, 20 and 25 minutes.
+    pub const MOVESPEED_UPGRADE: f32 = 25.0;
+    pub const MOVESPEED_BREAKPOINTS: [GameTimer; 4] = [
+        GameTimer::MINUTES_10,
+        GameTimer::MINUTES_15,
+        GameTimer::MINUTES_20,
+        GameTimer::MINUTES_25,
+    ];
+
+    pub(crate) fn new(kind: MinionType, spawn: GameTimer) -> Self {
+        let mut component = Self {
+            kind,
+            health: 0.0,
+            upgrades: Self::upgrades_at(spawn),
+            cooldown: 0.0,
+        };
+        component.health = component.current_stats(&spawn).health;
+        component
+    }
+
+    /// Upgrades of the minions of the wave spawning at `spawn`, they grow every 90 seconds.
+    pub fn upgrades_at(spawn: GameTimer) -> usize {
+        (ecs::spawners::wave::wave_number(spawn) - 1) / 3
+    }
+
+    /// Stat gained over `upgrades`, `early` for each of the first [`Self::EARLY_UPGRADES`] and
+    /// `late` for each of the following ones, up to [`Self::MAX_UPGRADES`].
+    fn scaling(upgrades: usize, early: f32, late: f32) -> f32 {
+        let upgrades = upgrades.min(Self::MAX_UPGRADES);
+        let early_upgrades = upgrades.min(Self::EARLY_UPGRADES);
+        early * early_upgrades as f32 + late * (upgrades - early_upgrades) as f32
+    }
+}
+
+impl WithUnitStats for MinionComponent {
+    fn base_stats(&self) -> UnitStatistics {
+        let mut stats = UnitStatistics {
+            movespeed: 325.0,
+            ..Default::default()
+        };
+        match self.kind {
+            MinionType::Melee => {
+                stats.health = 455.0;
+                stats.attack_speed = 1.25;
+                stats.attack_damage = 12.0;
+                stats.range = 110.0;
+            }
+            MinionType::Ranged => {
+                stats.health = 290.0;
+                stats.attack_speed = 0.667;
+                stats.attack_damage = 22.5;
+                stats.range = 550.0;
+            }
+            MinionType::Siege => {
+                stats.health = 850.0;
+                stats.attack_speed = 1.0;
+                stats.attack_damage = 41.0;
+                stats.range = 300.0;
+            }
+            MinionType::SuperMinion => {
+                stats.health = 1600.0;
+                stats.attack_damage = 230.0;
+                stats.health_regen = 67.5 / 5.0;
+                stats.attack_speed = 0.85;
+                stats.range = 170.0;
+                stats.armor = 100.0;
+                stats.magic_resist = -30.0;
+            }
+        }
+        stats
+    }
+
+    fn current_stats(&self, gs: &GameTimer) -> UnitStatistics {
+        let mut stats = self.base_stats();
+        let ms_upgrades = Self::MOVESPEED_BREAKPOINTS
+            .iter()
+            .filter(|breakpoint| *breakpoint <= gs)
+            .count();
+        stats.movespeed += Self::MOVESPEED_UPGRADE * ms_upgrades as f32;
+
+        // minions of the first wave spawn without upgrades, later ones grow faster after the
+        // first few upgrades
+        let upgrades = self.upgrades;
+        match self.kind {
+            MinionType::Melee => {
+                // https://leagueoflegends.fandom.com/wiki/Melee_minion
+                stats.health += Self::scaling(upgrades, 22.0, 32.25);
+                stats.attack_damage += Self::scaling(upgrades, 0.0, 3.41);
+            }
+            MinionType::Ranged => {
+                // https://leagueoflegends.fandom.com/wiki/Caster_minion
+                stats.health += Self::scaling(upgrades, 6.0, 8.25);
+                stats.attack_damage += Self::scaling(upgrades, 1.5, 4.5);
+            }
+            MinionType::Siege => {
+                // https://leagueoflegends.fandom.com/wiki/Siege_minion
+                stats.health += Self::scaling(upgrades, 62.0, 87.0);
+                stats.attack_damage += Self::scaling(upgrades, 1.5, 1.5);
+            }
+            MinionType::SuperMinion => {
+                // https://leagueoflegends.fandom.com/wiki/Super_minion
+                stats.health += Self::scaling(upgrades, 100.0, 100.0);
+                stats.attack_damage += Self::scaling(upgrades, 5.0, 5.0);
+                stats.health_regen += Self::scaling(upgrades, 0.0, 0.775);
+            }
+        }
+        stats
+    }
+}
+
+impl GoldCollectable for MinionComponent {
+    fn golds(&self) -> usize {
+        self.kind.gold(self.upgrades)
+    }
+
+    fn to_last_hit(&self) -> usize {
+        1
+    }
+
+    fn experience(&self) -> f32 {
+        self.kind.experience()
+    }
 }
 
 pub struct Minion<'store> {
@@ -32,6 +203,50 @@ pub struct Minion<'store> {
     pub(crate) entity: &'store Entity,
 }
 
+impl Minion<'_> {
+    pub fn get_state(&self) -> &MinionComponent {
+        &self.store.minions[self.get_specific_unchecked().unwrap()].1
+    }
+
+    /// Closest enemy minion within attack range, measured from edge to edge, or the closest
+    /// standing enemy turret or nexus that can be attacked at `timer` when no minion is in range.
+    pub fn target(&self, timer: GameTimer) -> Option<UnitId> {
+        let team = self.team()?;
+        let reach = self.get_state().base_stats().range + self.radius();
+        let minion = |guid: UnitId| self.store.get_minion(guid).is_some();
+        let structure = |guid: UnitId| {
+            let turret = self.store.get_turret(guid);
+            let nexus = self
+                .store
+                .get_nexus(team.opposite())
+                .filter(|n| n.guid() == guid);
+            turret.is_some_and(|t| t.is_up() && t.is_vulnerable(timer))
+                || nexus.is_some_and(|n| n.is_up() && n.is_vulnerable())
+        };
+
+        self.store
+            .nav
+            .units_in_range(*self.position(), reach)
+            .filter(|(guid, _)| guid.team() == Some(team.opposite()))
+            .filter(|(guid, _)| minion(*guid) || structure(*guid))
+            .map(|(guid, position)| (guid, (position.point - *self.position()).length()))
+            .min_by(|(a, da), (b, db)| minion(*b).cmp(&minion(*a)).then(da.total_cmp(db)))
+            .map(|(guid, _)| guid)
+    }
+
+    /// Enemy champions close enough to share the experience of the minion when it dies.
+    pub fn champions_in_xp_range(&self) -> Vec<UnitId> {
+        let team = self.team().map(|team| team.opposite());
+        self.store
+            .nav
+            .units_in_range(*self.position(), MinionComponent::XP_SHARE_RADIUS)
+            .map(|(guid, _)| guid)
+            .filter(|guid| guid.team().is_some() && guid.team() == team)
+            .filter(|guid| !guid.is_structure() && self.store.get_minion(*guid).is_none())
+            .collect()
+    }
+}
+
 impl<'store> EntityRef<'store> for Minion<'store> {
     fn store_ref(&self) -> &'store EntityStore {
         self.store
@@ -50,6 +265,11 @@ impl MinionMut<'_> {
     pub fn get_state(&self) -> &MinionComponent {
         &self.store.minions[self.get_specific_unchecked().unwrap()].1
     }
+
+    pub(crate) fn get_state_mut(&mut self) -> &mut MinionComponent {
+        let key = self.get_specific_unchecked().unwrap();
+        &mut self.store.minions[key].1
+    }
 }
 
 impl<'store> EntityRef<'store> for MinionMut<'store> {
@@ -82,7 +302,9 @@ pub struct MinionBuilder {
     kind: Option<MinionType>,
     lane: Option<Lane>,
     team: Option<Team>,
+    path: Option<std::sync::Arc<lyon::path::Path>>,
     offset: f32,
+    spawn: GameTimer,
 }
 
 impl MinionBuilder {
@@ -121,11 +343,22 @@ impl MinionBuilder {
         self
     }
 
+    /// Lane path the minion walks, usually one of [`EntityStore::lanes`].
+    pub fn set_path(mut self, path: std::sync::Arc<lyon::path::Path>) -> Self {
+        self.path = Some(path);
+        self
+    }
+
     pub fn set_offset(mut self, offset: f32) -> Self {
         self.offset = offset;
         self
     }
 
+    pub fn set_spawn(mut self, spawn: GameTimer) -> Self {
+        self.spawn = spawn;
+        self
+    }
+
     fn kind(&self) -> MinionType {
         self.kind.expect("minion kind was not set")
     }
@@ -139,7 +372,7 @@ impl MinionBuilder {
     }
 
     fn path(&self) -> &std::sync::Arc<lyon::path::Path> {
-        &LANE_PATHS[(self.team(), self.lane())]
+        self.path.as_ref().expect("minion path was not set")
     }
 
     fn radius(&self) -> f32 {
@@ -165,12 +398,252 @@ impl EntityBuilder for MinionBuilder {
     }
 
     fn pathfinding(&self) -> PathfindingComponent {
-        // TODO: Dynamic speed
-        PathfindingComponent::persistent(std::sync::Arc::clone(self.path()), 325.0)
+        let speed = MinionComponent::new(self.kind(), self.spawn)
+            .current_stats(&self.spawn)
+            .movespeed;
+        PathfindingComponent::persistent(std::sync::Arc::clone(self.path()), speed)
             .offset_position(self.offset)
     }
 
     fn specific(&self) -> SpecificComponentBuilder {
-        SpecificComponentBuilder::Minion(MinionComponent { kind: self.kind() })
+        SpecificComponentBuilder::Minion(MinionComponent::new(self.kind(), self.spawn))
+    }
+}
+
+/// Minions walk faster once `timer` passes one of the movespeed breakpoints.
+pub(crate) fn update_movespeed(store: &mut EntityStore, timer: GameTimer) {
+    for minion in store.minions_mut() {
+        let speed = minion.get_state().current_stats(&timer).movespeed;
+        minion.pathfinding_component_mut().speed = speed;
+    }
+}
+
+/// Minions with an enemy in range attack it for `step` instead of walking, damage is dealt
+/// simultaneously and units without health left are removed. Returns the minions that fought.
+/// `timer` is the game time used to scale their stats.
+pub(crate) fn fight(store: &mut EntityStore, timer: GameTimer, step: GameTimer) -> HashSet<UnitId> {
+    let targets = store
+        .minions()
+        .filter_map(|minion| Some((minion.guid(), minion.target(timer)?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut damage = HashMap::<UnitId, f32>::new();
+    for mut minion in store.minions_mut() {
+        let stats = minion.get_state().current_stats(&timer);
+        let target = targets.get(&minion.guid());
+        let state = minion.get_state_mut();
+        let elapsed = step.as_secs_f32();
+
+        let Some(target) = target else {
+            state.cooldown = (state.cooldown - elapsed).max(0.0);
+            continue;
+        };
+
+        let mut attacks = 0;
+        while state.cooldown <= elapsed {
+            attacks += 1;
+            state.cooldown += 1.0 / stats.attack_speed;
+        }
+        state.cooldown -= elapsed;
+        *damage.entry(*target).or_default() += attacks as f32 * stats.attack_damage;
+    }
+
+    for (guid, damage) in damage {
+        store.deal_damage(guid, Damage::Physical(damage), timer);
+    }
+
+    targets.into_keys().collect()
+}
+
+#[test]
+fn opposing_waves_fight_where_they_meet() {
+    use crate::Engine;
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let step = GameTimer(std::time::Duration::from_secs(1));
+
+    let mut damaged = false;
+    while engine.timer < GameTimer::FIRST_SPAWN + GameTimer(std::time::Duration::from_secs(50)) {
+        engine.on_step(&mut store, step);
+
+        damaged |= store
+            .minions()
+            .any(|minion| minion.get_state().health < minion.get_state().base_stats().health);
+
+        // distance walked from the blue end of each lane by the most advanced minion of a team
+        for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+            let lengths = store
+                .lanes
+                .lengths(&store.lanes[(Team::Blue, lane)])
+                .unwrap();
+            let walked = |team: Team| {
+                store
+                    .minions()
+                    .filter(|minion| {
+                        minion.guid().lane() == Some(lane) && minion.team() == Some(team)
+                    })
+                    .map(|minion| minion.pathfinding_component().position)
+                    .max_by(f32::total_cmp)
+            };
+            if let (Some(blue), Some(red)) = (walked(Team::Blue), walked(Team::Red)) {
+                assert!(
+                    blue <= lengths.length() - red,
+                    "{lane:?} waves crossed at {:?}",
+                    engine.timer
+                );
+            }
+        }
+    }
+    assert!(damaged);
+
+    // two waves of six minions spawned in every lane for each team, some died fighting
+    assert!(store.minions().count() < 2 * 6 * 6);
+    assert!(store.minions().count() > 0);
+}
+
+#[test]
+fn minion_stats_match_the_wiki() {
+    let at = |minutes: u64, seconds: u64| {
+        GameTimer(std::time::Duration::from_secs(60 * minutes + seconds))
+    };
+    // first wave, first upgrade, 12 upgrades at 20:05 and the last upgrade an hour in
+    let expected = [
+        (MinionType::Melee, at(1, 5), 455.0, 12.0),
+        (MinionType::Melee, at(2, 35), 477.0, 12.0),
+        (MinionType::Melee, at(20, 5), 790.75, 35.87),
+        (MinionType::Melee, at(60, 5), 1210.0, 80.2),
+        (MinionType::Ranged, at(1, 5), 290.0, 22.5),
+        (MinionType::Ranged, at(2, 35), 296.0, 24.0),
+        (MinionType::Ranged, at(20, 5), 377.75, 61.5),
+        (MinionType::Siege, at(1, 5), 850.0, 41.0),
+        (MinionType::Siege, at(2, 35), 912.0, 42.5),
+        (MinionType::Siege, at(20, 5), 1769.0, 59.0),
+        (MinionType::SuperMinion, at(1, 5), 1600.0, 230.0),
+        (MinionType::SuperMinion, at(20, 5), 2800.0, 290.0),
+    ];
+    for (kind, spawn, health, attack_damage) in expected {
+        let stats = MinionComponent::new(kind, spawn).current_stats(&spawn);
+        assert!(
+            (stats.health - health).abs() < 1e-3,
+            "{kind:?} {spawn:?} {stats:?}"
+        );
+        assert!(
+            (stats.attack_damage - attack_damage).abs() < 1e-3,
+            "{kind:?} {spawn:?} {stats:?}"
+        );
+    }
+
+    // super minions regenerate faster once past the early upgrades
+    let regen = |spawn| {
+        MinionComponent::new(MinionType::SuperMinion, spawn)
+            .current_stats(&spawn)
+            .health_regen
+    };
+    assert_eq!(regen(at(1, 5)), 67.5 / 5.0);
+    assert!((regen(at(20, 5)) - (67.5 / 5.0 + 7.0 * 0.775)).abs() < 1e-3);
+}
+
+#[test]
+fn minion_stats_scale_with_game_time() {
+    use crate::Engine;
+
+    let early = MinionComponent::new(MinionType::Melee, GameTimer::FIRST_SPAWN);
+    let late = MinionComponent::new(MinionType::Melee, GameTimer::MINUTES_20);
+    assert_eq!(early.upgrades, 0);
+    assert_eq!(early.health, 455.0);
+    assert!(late.upgrades > early.upgrades);
+    assert!(late.health > early.health);
+    let ad = |minion: &MinionComponent| minion.current_stats(&GameTimer::MINUTES_20).attack_damage;
+    assert!(ad(&late) > ad(&early));
+
+    let siege = |spawn| MinionComponent::new(MinionType::Siege, spawn).golds();
+    assert_eq!(siege(GameTimer::FIRST_SPAWN), MinionComponent::BIG_GOLD);
+    assert!(siege(GameTimer::MINUTES_20) > siege(GameTimer::FIRST_SPAWN));
+    assert_eq!(siege(GameTimer(std::time::Duration::from_secs(3600))), 90);
+
+    // super minions are worth the same whenever they spawn
+    let super_minion = |spawn| MinionComponent::new(MinionType::SuperMinion, spawn).golds();
+    assert_eq!(super_minion(GameTimer::FIRST_SPAWN), 60);
+    assert_eq!(super_minion(GameTimer::MINUTES_20), 60);
+    assert_eq!(
+        super_minion(GameTimer(std::time::Duration::from_secs(3600))),
+        60
+    );
+
+    // every minion walks faster after each breakpoint
+    let movespeed = |timer: GameTimer| early.current_stats(&timer).movespeed;
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    assert_eq!(movespeed(GameTimer::MINUTES_10 - second), 325.0);
+    assert_eq!(movespeed(GameTimer::MINUTES_10), 350.0);
+    assert_eq!(movespeed(GameTimer::MINUTES_20), 400.0);
+    assert_eq!(
+        movespeed(GameTimer::MINUTES_25 + GameTimer::MINUTES_10),
+        425.0
+    );
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let walk_at = |store: &EntityStore, speed| {
+        store
+            .minions()
+            .all(|minion| minion.pathfinding_component().speed == speed)
+    };
+    engine.timer = GameTimer::MINUTES_10 - GameTimer::WAVE_TRAVEL;
+    engine.on_step(&mut store, second);
+    assert!(store.minions().count() > 0);
+    assert!(walk_at(&store, 325.0));
+    for minion in store.minions() {
+        let state = minion.get_state();
+        assert_eq!(state.upgrades, 5);
+        assert_eq!(state.health, state.current_stats(&engine.timer).health);
+    }
+
+    engine.timer = GameTimer::MINUTES_10 - second;
+    engine.on_step(&mut store, second);
+    assert!(walk_at(&store, 350.0));
+}
+
+#[test]
+fn champions_around_dying_minions_share_experience() {
+    use crate::ecs::entity::TestChampion;
+
+    let (_, mut store) = crate::MinimapEngine::init();
+    let minion = store.spawn(
+        MinionBuilder::siege()
+            .set_team(Team::Red)
+            .set_lane(Lane::Mid)
+            .set_path(std::sync::Arc::clone(&store.lanes[(Team::Red, Lane::Mid)])),
+    );
+    let center = *store.get_minion(minion).unwrap().position();
+    let near = [
+        store.spawn(TestChampion(
+            Team::Blue,
+            center + lyon::math::vector(500.0, 0.0),
+        )),
+        store.spawn(TestChampion(
+            Team::Blue,
+            center + lyon::math::vector(0.0, 1500.0),
+        )),
+    ];
+    let far = store.spawn(TestChampion(
+        Team::Blue,
+        center + lyon::math::vector(-2000.0, 0.0),
+    ));
+    let ally = store.spawn(TestChampion(
+        Team::Red,
+        center + lyon::math::vector(0.0, -500.0),
+    ));
+
+    store.deal_damage(minion, Damage::True(100.0), GameTimer::GAME_START);
+    assert_eq!(store.experience(near[0]), 0.0);
+
+    store.deal_damage(minion, Damage::True(10000.0), GameTimer::GAME_START);
+    assert!(store.get_minion(minion).is_none());
+    for champion in near {
+        assert_eq!(
+            store.experience(champion),
+            MinionType::Siege.experience() / 2.0
+        );
     }
+    assert_eq!(store.experience(far), 0.0);
+    assert_eq!(store.experience(ally), 0.0);
 }
@@ -1,3 +1,6 @@
+pub mod camp;
+pub mod champion;
 pub mod minion;
+pub mod monster;
 
 // pub mod old_minion;
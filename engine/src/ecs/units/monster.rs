@@ -0,0 +1,426 @@
+use std::time::Duration;
+
+use lyon::math::Point;
+
+use crate::{
+    core::{GameTimer, Team},
+    ecs::{
+        entity::{Entity, EntityBuilder, EntityRef, EntityRefCrateExt, SpecificComponentBuilder},
+        generic::{pathfinding::PathfindingComponent, PositionComponent},
+        store::EntityStore,
+        structures::MAP_BOUNDS,
+        UnitId,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MonsterKind {
+    Dragon,
+    VoidGrubs,
+    Herald,
+    Baron,
+}
+
+impl MonsterKind {
+    pub const ALL: [Self; 4] = [Self::Dragon, Self::VoidGrubs, Self::Herald, Self::Baron];
+
+    pub fn first_spawn(&self) -> GameTimer {
+        let secs = match self {
+            MonsterKind::Dragon => 5 * 60,
+            MonsterKind::VoidGrubs => 6 * 60,
+            MonsterKind::Herald => 14 * 60,
+            MonsterKind::Baron => 20 * 60,
+        };
+        GameTimer(Duration::from_secs(secs))
+    }
+
+    /// Time between the death of the monster and its next spawn, `None` for monsters that don't
+    /// come back.
+    pub fn respawn(&self) -> Option<GameTimer> {
+        let secs = match self {
+            MonsterKind::Dragon => 5 * 60,
+            MonsterKind::VoidGrubs => 4 * 60,
+            MonsterKind::Herald => return None,
+            MonsterKind::Baron => 6 * 60,
+        };
+        Some(GameTimer(Duration::from_secs(secs)))
+    }
+
+    /// Number of times the monster spawns over a game, `None` when it keeps respawning.
+    pub fn spawns(&self) -> Option<usize> {
+        match self {
+            MonsterKind::VoidGrubs => Some(2),
+            MonsterKind::Herald => Some(1),
+            MonsterKind::Dragon | MonsterKind::Baron => None,
+        }
+    }
+
+    /// Time at which the monster leaves the pit for the next one to spawn.
+    pub fn despawn(&self) -> Option<GameTimer> {
+        let secs = match self {
+            MonsterKind::VoidGrubs => 13 * 60 + 45,
+            MonsterKind::Herald => 19 * 60 + 45,
+            MonsterKind::Dragon | MonsterKind::Baron => return None,
+        };
+        Some(GameTimer(Duration::from_secs(secs)))
+    }
+
+    /// Center of the pit of the monster, void grubs, herald and baron share the top one.
+    pub fn pit(&self) -> Point {
+        match self {
+            MonsterKind::Dragon => Point::new(9866.0, MAP_BOUNDS.height - 4414.0),
+            MonsterKind::VoidGrubs | MonsterKind::Herald | MonsterKind::Baron => {
+                Point::new(5007.0, MAP_BOUNDS.height - 10471.0)
+            }
+        }
+    }
+
+    fn radius(&self) -> f32 {
+        match self {
+            MonsterKind::VoidGrubs => 100.0,
+            MonsterKind::Dragon | MonsterKind::Herald | MonsterKind::Baron => 250.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DragonKind {
+    Infernal,
+    Mountain,
+    Ocean,
+    Cloud,
+    Hextech,
+    Chemtech,
+    Elder,
+}
+
+/// Order in which the elemental dragons spawn: the first two ones, then the dragon of the rift
+/// until a team takes its soul, after which only elder dragons spawn.
+#[derive(Debug, Clone)]
+pub struct DragonSequence {
+    pub first: DragonKind,
+    pub second: DragonKind,
+    pub rift: DragonKind,
+    /// Dragons slain so far, with the team that took them.
+    pub(crate) slain: Vec<(Team, DragonKind)>,
+}
+
+impl DragonSequence {
+    /// Elemental dragons a team has to take to get the dragon soul.
+    pub const SOUL: usize = 4;
+    pub const ELDER_RESPAWN: GameTimer = GameTimer(Duration::from_secs(6 * 60));
+
+    pub fn new(first: DragonKind, second: DragonKind, rift: DragonKind) -> Self {
+        Self {
+            first,
+            second,
+            rift,
+            slain: vec![],
+        }
+    }
+
+    /// Team who took the dragon soul.
+    pub fn soul(&self) -> Option<Team> {
+        [Team::Blue, Team::Red].into_iter().find(|team| {
+            self.slain
+                .iter()
+                .filter(|(by, kind)| by == team && *kind != DragonKind::Elder)
+                .count()
+                >= Self::SOUL
+        })
+    }
+
+    /// Kind of the next dragon to spawn.
+    pub fn next(&self) -> DragonKind {
+        if self.soul().is_some() {
+            return DragonKind::Elder;
+        }
+        match self.slain.len() {
+            0 => self.first,
+            1 => self.second,
+            _ => self.rift,
+        }
+    }
+
+    pub fn slain(&self) -> &[(Team, DragonKind)] {
+        &self.slain
+    }
+}
+
+impl Default for DragonSequence {
+    fn default() -> Self {
+        Self::new(
+            DragonKind::Infernal,
+            DragonKind::Ocean,
+            DragonKind::Mountain,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MonsterState {
+    /// The monster spawns at the given time.
+    Waiting(GameTimer),
+    Alive,
+    /// The monster won't spawn again this game.
+    Gone,
+}
+
+#[derive(Debug)]
+pub struct MonsterComponent {
+    pub kind: MonsterKind,
+    pub(crate) state: MonsterState,
+    /// Times the monster spawned so far.
+    pub(crate) spawned: usize,
+    pub(crate) dragons: Option<DragonSequence>,
+}
+
+impl MonsterComponent {
+    pub fn state(&self) -> MonsterState {
+        self.state
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.state == MonsterState::Alive
+    }
+
+    pub fn next_spawn(&self) -> Option<GameTimer> {
+        match self.state {
+            MonsterState::Waiting(timer) => Some(timer),
+            MonsterState::Alive | MonsterState::Gone => None,
+        }
+    }
+
+    pub fn dragons(&self) -> Option<&DragonSequence> {
+        self.dragons.as_ref()
+    }
+
+    /// The monster was killed by `team` at `timer`.
+    pub(crate) fn kill(&mut self, team: Team, timer: GameTimer) {
+        if !self.is_up() {
+            return;
+        }
+
+        let mut respawn = self.kind.respawn();
+        if let Some(dragons) = self.dragons.as_mut() {
+            dragons.slain.push((team, dragons.next()));
+            if dragons.next() == DragonKind::Elder {
+                respawn = Some(DragonSequence::ELDER_RESPAWN);
+            }
+        }
+
+        let exhausted = self
+            .kind
+            .spawns()
+            .is_some_and(|spawns| self.spawned >= spawns);
+        self.state = match respawn {
+            Some(respawn) if !exhausted => self.waiting(timer + respawn),
+            _ => MonsterState::Gone,
+        };
+    }
+
+    /// Wait for `timer`, unless the monster is already gone from the pit by then.
+    fn waiting(&self, timer: GameTimer) -> MonsterState {
+        match self.kind.despawn() {
+            Some(despawn) if despawn <= timer => MonsterState::Gone,
+            _ => MonsterState::Waiting(timer),
+        }
+    }
+}
+
+pub struct Monster<'store> {
+    pub(crate) store: &'store EntityStore,
+    pub(crate) entity: &'store Entity,
+}
+
+impl Monster<'_> {
+    pub fn get_state(&self) -> &MonsterComponent {
+        &self.store.monsters[self.get_specific_unchecked().unwrap()].1
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.get_state().is_up()
+    }
+}
+
+impl<'store> EntityRef<'store> for Monster<'store> {
+    fn store_ref(&self) -> &'store EntityStore {
+        self.store
+    }
+
+    fn entity(&self) -> &Entity {
+        self.entity
+    }
+}
+
+impl<'a> std::fmt::Debug for Monster<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Monster")
+            .field("id", &self.entity.guid)
+            .field("position", &self.position())
+            .field("state", self.get_state())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MonsterIndex(pub MonsterKind);
+
+impl MonsterIndex {
+    pub const DRAGON: Self = Self(MonsterKind::Dragon);
+    pub const VOID_GRUBS: Self = Self(MonsterKind::VoidGrubs);
+    pub const HERALD: Self = Self(MonsterKind::Herald);
+    pub const BARON: Self = Self(MonsterKind::Baron);
+}
+
+impl EntityBuilder for MonsterIndex {
+    fn guid(&self) -> UnitId {
+        UnitId::from(*self)
+    }
+
+    fn position(&self) -> PositionComponent {
+        PositionComponent {
+            point: self.0.pit(),
+            radius: self.0.radius(),
+        }
+    }
+
+    fn pathfinding(&self) -> PathfindingComponent {
+        PathfindingComponent::no_path()
+    }
+
+    fn specific(&self) -> SpecificComponentBuilder {
+        SpecificComponentBuilder::Monster(MonsterComponent {
+            kind: self.0,
+            state: MonsterState::Waiting(self.0.first_spawn()),
+            spawned: 0,
+            dragons: (self.0 == MonsterKind::Dragon).then(DragonSequence::default),
+        })
+    }
+}
+
+/// Spawn the monsters whose timer is over at `timer`, and remove the ones leaving their pit.
+pub(crate) fn update(store: &mut EntityStore, timer: GameTimer) {
+    for (_, (_, monster)) in store.monsters.iter_mut() {
+        if monster
+            .kind
+            .despawn()
+            .is_some_and(|despawn| despawn <= timer)
+        {
+            monster.state = MonsterState::Gone;
+        }
+        if monster.next_spawn().is_some_and(|spawn| spawn <= timer) {
+            monster.state = MonsterState::Alive;
+            monster.spawned += 1;
+        }
+    }
+}
+
+#[test]
+fn epic_monsters_spawn_on_their_timers() {
+    use crate::Engine;
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    assert_eq!(store.monsters().count(), MonsterKind::ALL.len());
+    assert!(store.monsters().all(|monster| !monster.is_up()));
+    assert_eq!(
+        store.upcoming_monsters(),
+        MonsterKind::ALL
+            .iter()
+            .map(|kind| (*kind, kind.first_spawn()))
+            .collect::<Vec<_>>()
+    );
+
+    let second = GameTimer(Duration::from_secs(1));
+    let is_up =
+        |store: &EntityStore, index: MonsterIndex| store.get_monster(index).unwrap().is_up();
+    engine.timer = MonsterKind::Dragon.first_spawn() - second;
+    engine.on_step(&mut store, second);
+    assert!(is_up(&store, MonsterIndex::DRAGON));
+    assert_eq!(store.next_dragon(), Some(DragonKind::Infernal));
+    assert_eq!(
+        store.next_monster(),
+        Some((MonsterKind::VoidGrubs, MonsterKind::VoidGrubs.first_spawn()))
+    );
+
+    // the dragons follow their sequence, and the soul brings the elder dragon
+    let mut killed = engine.timer;
+    store.kill_monster(MonsterKind::Dragon, Team::Blue, killed);
+    assert_eq!(
+        store
+            .get_monster(MonsterIndex::DRAGON)
+            .unwrap()
+            .get_state()
+            .next_spawn(),
+        Some(killed + MonsterKind::Dragon.respawn().unwrap())
+    );
+    assert_eq!(store.next_dragon(), Some(DragonKind::Ocean));
+    let respawn = MonsterKind::Dragon.respawn().unwrap();
+    for (wait, expected) in [
+        (respawn, DragonKind::Mountain),
+        (respawn, DragonKind::Mountain),
+        (respawn, DragonKind::Elder),
+        (DragonSequence::ELDER_RESPAWN, DragonKind::Elder),
+    ] {
+        engine.timer = killed + wait;
+        engine.on_step(&mut store, second);
+        assert!(is_up(&store, MonsterIndex::DRAGON));
+        killed = engine.timer;
+        store.kill_monster(MonsterKind::Dragon, Team::Blue, killed);
+        assert_eq!(store.next_dragon(), Some(expected));
+    }
+    let dragon = store.get_monster(MonsterIndex::DRAGON).unwrap();
+    let dragons = dragon.get_state().dragons().unwrap();
+    assert_eq!(dragons.soul(), Some(Team::Blue));
+    assert_eq!(dragons.slain().len(), DragonSequence::SOUL + 1);
+    assert_eq!(
+        dragon.get_state().next_spawn(),
+        Some(killed + DragonSequence::ELDER_RESPAWN)
+    );
+
+    // void grubs spawn twice then leave the pit to the herald
+    let mut store = crate::MinimapEngine::init().1;
+    update(&mut store, MonsterKind::VoidGrubs.first_spawn());
+    assert!(is_up(&store, MonsterIndex::VOID_GRUBS));
+    killed = MonsterKind::VoidGrubs.first_spawn() + second;
+    store.kill_monster(MonsterKind::VoidGrubs, Team::Red, killed);
+    let respawn = killed + MonsterKind::VoidGrubs.respawn().unwrap();
+    assert_eq!(
+        store
+            .get_monster(MonsterIndex::VOID_GRUBS)
+            .unwrap()
+            .get_state()
+            .next_spawn(),
+        Some(respawn)
+    );
+    update(&mut store, respawn);
+    assert!(is_up(&store, MonsterIndex::VOID_GRUBS));
+    store.kill_monster(MonsterKind::VoidGrubs, Team::Red, respawn + second);
+    assert_eq!(
+        store
+            .get_monster(MonsterIndex::VOID_GRUBS)
+            .unwrap()
+            .get_state()
+            .state(),
+        MonsterState::Gone
+    );
+
+    // the herald spawns once, and is gone before baron
+    update(&mut store, MonsterKind::Herald.first_spawn());
+    assert!(is_up(&store, MonsterIndex::HERALD));
+    assert_eq!(
+        store.next_monster(),
+        Some((MonsterKind::Baron, MonsterKind::Baron.first_spawn()))
+    );
+    update(&mut store, MonsterKind::Herald.despawn().unwrap());
+    assert_eq!(
+        store
+            .get_monster(MonsterIndex::HERALD)
+            .unwrap()
+            .get_state()
+            .state(),
+        MonsterState::Gone
+    );
+    update(&mut store, MonsterKind::Baron.first_spawn());
+    assert!(is_up(&store, MonsterIndex::BARON));
+}
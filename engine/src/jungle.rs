@@ -0,0 +1,900 @@
+use std::{collections::HashMap, ops::Range};
+
+use lyon::math::Point;
+
+use crate::{
+    core::{GameTimer, Lane, Team},
+    ecs::{
+        entity::{EntityBuilder, EntityMut, EntityRef, EntityRefCrateExt},
+        generic::pathfinding::Objective,
+        store::EntityStore,
+        structures::nexus::NexusIndex,
+        UnitId,
+    },
+    stats::{Damage, UnitStatistics, WithUnitStats},
+    units::{
+        camp::{CampIndex, CampKind},
+        champion::{ChampionBuilder, ChampionComponent},
+    },
+    Engine, MinimapEngine,
+};
+
+/// How fast a champion clears the jungle: its movespeed between camps and the time it spends
+/// killing each kind of camp.
+#[derive(Debug, Clone)]
+pub struct ClearProfile {
+    pub movespeed: f32,
+    pub clear_times: HashMap<CampKind, GameTimer>,
+}
+
+impl ClearProfile {
+    pub fn new(movespeed: f32) -> Self {
+        Self {
+            movespeed,
+            clear_times: HashMap::new(),
+        }
+    }
+
+    pub fn set_clear_time(mut self, kind: CampKind, time: GameTimer) -> Self {
+        self.clear_times.insert(kind, time);
+        self
+    }
+
+    /// Profile of a champion with `stats`, from the time it takes to kill each camp.
+    pub fn from_stats(stats: &UnitStatistics, smite: bool) -> Self {
+        CampKind::ALL.into_iter().fold(
+            Self::new(stats.movespeed),
+            |profile, kind| match clear_camp(stats, &kind.monsters(), smite) {
+                Some(clear) => profile.set_clear_time(kind, clear.time),
+                None => profile,
+            },
+        )
+    }
+
+    /// Time spent killing a camp of `kind`, `None` for camps the champion does not take.
+    pub fn clear_time(&self, kind: CampKind) -> Option<GameTimer> {
+        self.clear_times.get(&kind).copied()
+    }
+}
+
+/// True damage dealt by smite.
+pub const SMITE_DAMAGE: f32 = 600.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CampClear {
+    pub time: GameTimer,
+    pub health_lost: f32,
+}
+
+/// Time for a champion with `stats` to kill `monsters` one after the other with its basic
+/// attacks, smiting the first one when `smite` is set, and the health it loses to them
+/// meanwhile. Lifesteal and vamps are fractions of the damage dealt. Returns `None` if the
+/// champion can't kill the monsters.
+pub fn clear_camp(
+    stats: &UnitStatistics,
+    monsters: &[UnitStatistics],
+    smite: bool,
+) -> Option<CampClear> {
+    if stats.attack_speed <= 0.0 {
+        return None;
+    }
+    // critical strikes are averaged over the attacks
+    let crit = stats.critical_strike_chance.clamp(0.0, 1.0)
+        * (stats.critical_strike_damage - 1.0).max(0.0);
+    let hit = Damage::Physical(stats.attack_damage * (1.0 + crit));
+
+    let (mut attacks, mut dealt, mut taken) = (0, 0.0, 0.0);
+    for (i, monster) in monsters.iter().enumerate() {
+        let health = if smite && i == 0 {
+            monster.health - SMITE_DAMAGE
+        } else {
+            monster.health
+        };
+        if health > 0.0 {
+            let damage = hit.from_attacker(stats, monster);
+            if damage <= 0.0 {
+                return None;
+            }
+            attacks += (health / damage).ceil() as usize;
+            dealt += health;
+        }
+
+        // every monster fights back until it dies
+        let death = attacks.saturating_sub(1) as f32 / stats.attack_speed;
+        let damage = Damage::Physical(monster.attack_damage).from_attacker(monster, stats);
+        taken += damage * monster.attack_speed * death;
+    }
+
+    let time = attacks.saturating_sub(1) as f32 / stats.attack_speed;
+    let vamp = stats.lifesteal + stats.physicalvamp + stats.omnivamp;
+    let healed = dealt * vamp + stats.health_regen / 5.0 * time;
+    Some(CampClear {
+        time: GameTimer(std::time::Duration::from_secs_f32(time)),
+        health_lost: (taken - healed).max(0.0),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteGoal {
+    /// Earn as much gold and experience as possible within the time budget.
+    Resources,
+    /// Reach the given level as soon as possible.
+    Level(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteStep {
+    pub camp: CampIndex,
+    /// Time at which the champion reaches the camp.
+    pub arrival: GameTimer,
+    /// Time at which the champion starts attacking the camp, once it is up.
+    pub start: GameTimer,
+    pub cleared: GameTimer,
+}
+
+/// Camps to clear one after the other, with the gold and experience they give.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Route {
+    pub steps: Vec<RouteStep>,
+    pub gold: usize,
+    pub experience: f32,
+}
+
+impl Route {
+    /// Time at which the last camp of the route is cleared.
+    pub fn end(&self) -> Option<GameTimer> {
+        self.steps.last().map(|step| step.cleared)
+    }
+
+    /// Level reached by a champion who started the route at level 1.
+    pub fn level(&self) -> usize {
+        ChampionComponent::level_at(self.experience)
+    }
+
+    fn value(&self) -> f32 {
+        self.gold as f32 + self.experience
+    }
+
+    fn push(&mut self, step: RouteStep) {
+        let kind = step.camp.1;
+        self.gold += kind.gold();
+        self.experience += kind.experience();
+        self.steps.push(step);
+    }
+
+    fn pop(&mut self) {
+        if let Some(step) = self.steps.pop() {
+            self.gold -= step.camp.1.gold();
+            self.experience -= step.camp.1.experience();
+        }
+    }
+
+    /// Play the route in the engine: `champion` walks to each camp and kills it when the route
+    /// clears it, earning its gold and experience.
+    pub fn replay(&self, engine: &mut MinimapEngine, store: &mut EntityStore, champion: UnitId) {
+        for step in &self.steps {
+            if let Some(unit) = store.get_unit_mut(champion) {
+                unit.add_objective(Objective::Unit(step.camp.into()));
+            }
+            if step.cleared > engine.timer {
+                engine.on_step(store, step.cleared - engine.timer);
+            }
+
+            // the champion only earns camps that were up when it got there
+            let up = store.get_camp(step.camp).is_some_and(|camp| camp.is_up());
+            store.kill_camp(step.camp, step.cleared);
+            if up {
+                store.share_gold(&[champion], step.camp.1.gold() as f32);
+                store.share_experience(&[champion], step.camp.1.experience());
+            }
+
+            let level = ChampionComponent::level_at(store.experience(champion));
+            if let Some(mut champion) = store.get_champion_mut(champion) {
+                champion.set_level(level);
+            }
+        }
+    }
+}
+
+/// Plans jungle clears over a set of camps, from the travel times between them and the state of
+/// the camps in the store.
+pub struct RoutePlanner<'store> {
+    store: &'store EntityStore,
+    profile: ClearProfile,
+    camps: Vec<CampIndex>,
+    /// Travel time from each camp to the others, `None` when there is no path.
+    travel: Vec<Vec<Option<GameTimer>>>,
+}
+
+impl<'store> RoutePlanner<'store> {
+    /// Planner over the camps on the side of `team` and the scuttle crabs.
+    pub fn new(store: &'store EntityStore, team: Team, profile: ClearProfile) -> Self {
+        let camps = CampIndex::ALL
+            .into_iter()
+            .filter(|camp| camp.0.is_none() || camp.0 == Some(team));
+        Self::with_camps(store, camps, profile)
+    }
+
+    /// Planner over the given camps, e.g. to invade the enemy jungle.
+    pub fn with_camps(
+        store: &'store EntityStore,
+        camps: impl IntoIterator<Item = CampIndex>,
+        profile: ClearProfile,
+    ) -> Self {
+        let camps = camps
+            .into_iter()
+            .filter(|camp| store.get_camp(*camp).is_some())
+            .collect::<Vec<_>>();
+        let travel = camps
+            .iter()
+            .map(|from| {
+                let from = *store.get_camp(*from).unwrap().position();
+                camps
+                    .iter()
+                    .map(|to| {
+                        store
+                            .travel_time(
+                                from,
+                                &Objective::Unit((*to).into()),
+                                profile.movespeed,
+                                ChampionBuilder::RADIUS,
+                            )
+                            .ok()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            store,
+            profile,
+            camps,
+            travel,
+        }
+    }
+
+    /// Time to walk from camp `from` to camp `to`.
+    pub fn travel_time(&self, from: CampIndex, to: CampIndex) -> Option<GameTimer> {
+        let from = self.camps.iter().position(|camp| *camp == from)?;
+        let to = self.camps.iter().position(|camp| *camp == to)?;
+        self.travel[from][to]
+    }
+
+    /// Time the camps are up, from the state of the store at `at`.
+    fn available(&self, at: GameTimer) -> Vec<GameTimer> {
+        self.camps
+            .iter()
+            .map(|camp| {
+                self.store
+                    .get_camp(*camp)
+                    .and_then(|camp| camp.get_state().next_spawn())
+                    .unwrap_or(at)
+            })
+            .collect()
+    }
+
+    /// Clear the camp `next` after the ones already in `route`, `None` when it can't be done.
+    fn step(
+        &self,
+        route: &Route,
+        next: usize,
+        at: GameTimer,
+        available: &[GameTimer],
+    ) -> Option<RouteStep> {
+        let camp = self.camps[next];
+        let arrival = match route.steps.last() {
+            Some(last) => {
+                let from = self.camps.iter().position(|camp| *camp == last.camp)?;
+                last.cleared + self.travel[from][next]?
+            }
+            None => at,
+        };
+        let start = std::cmp::max(arrival, available[next]);
+        Some(RouteStep {
+            camp,
+            arrival,
+            start,
+            cleared: start + self.profile.clear_time(camp.1)?,
+        })
+    }
+
+    /// Time the camps of `order` one after the other, the first one being reached at `at`.
+    /// Returns `None` if a camp can't be reached or is not taken by the champion.
+    pub fn evaluate(&self, order: &[CampIndex], at: GameTimer) -> Option<Route> {
+        let mut available = self.available(at);
+        let mut route = Route::default();
+        for camp in order {
+            let next = self.camps.iter().position(|index| index == camp)?;
+            let step = self.step(&route, next, at, &available)?;
+            available[next] = step.cleared + camp.1.respawn();
+            route.push(step);
+        }
+        Some(route)
+    }
+
+    /// Best route starting with the `start` camp reached at `at`, clearing its last camp within
+    /// `budget`. Returns `None` if not even the first camp can be cleared in time, or if the
+    /// level goal can't be reached.
+    pub fn plan(
+        &self,
+        start: CampIndex,
+        at: GameTimer,
+        budget: GameTimer,
+        goal: RouteGoal,
+    ) -> Option<Route> {
+        let mut search = Search {
+            planner: self,
+            goal,
+            at,
+            deadline: at + budget,
+            best: None,
+            seen: HashMap::new(),
+        };
+        let mut route = Route::default();
+        let mut available = self.available(at);
+        let mut cleared = vec![0; self.camps.len()];
+        let first = self.camps.iter().position(|camp| *camp == start)?;
+        search.visit(first, &mut route, &mut available, &mut cleared);
+        search.best
+    }
+}
+
+/// End of a route, with the times the camps are up again after it.
+type SearchState = (GameTimer, Vec<GameTimer>);
+
+/// Depth first search over the camps left to clear, the state of the camps changes with the
+/// time at which the route reaches them.
+struct Search<'planner, 'store> {
+    planner: &'planner RoutePlanner<'store>,
+    goal: RouteGoal,
+    at: GameTimer,
+    deadline: GameTimer,
+    best: Option<Route>,
+    /// End of the routes ending on each camp after clearing the camps as many times, with the
+    /// times the camps are up again after them. Routes done no sooner than one of them, with no
+    /// camp up again sooner, are not explored further.
+    seen: HashMap<(usize, Vec<u8>), Vec<SearchState>>,
+}
+
+impl Search<'_, '_> {
+    fn visit(
+        &mut self,
+        next: usize,
+        route: &mut Route,
+        available: &mut [GameTimer],
+        cleared: &mut [u8],
+    ) {
+        let Some(step) = self.planner.step(route, next, self.at, available) else {
+            return;
+        };
+        if step.cleared > self.deadline {
+            return;
+        }
+        if let (RouteGoal::Level(_), Some(end)) =
+            (self.goal, self.best.as_ref().and_then(Route::end))
+        {
+            if step.cleared >= end {
+                return;
+            }
+        }
+
+        let camp = self.planner.camps[next];
+        let respawn = std::mem::replace(&mut available[next], step.cleared + camp.1.respawn());
+        cleared[next] += 1;
+
+        // a route is only as good as another one clearing the same camps when it is done no
+        // later and every camp is up again no later
+        let no_later = |a: &[GameTimer], b: &[GameTimer]| a.iter().zip(b).all(|(a, b)| a <= b);
+        let states = self.seen.entry((next, cleared.to_vec())).or_default();
+        if states
+            .iter()
+            .any(|(at, camps)| *at <= step.cleared && no_later(camps, available))
+        {
+            available[next] = respawn;
+            cleared[next] -= 1;
+            return;
+        }
+        states.retain(|(at, camps)| !(step.cleared <= *at && no_later(available, camps)));
+        states.push((step.cleared, available.to_vec()));
+        route.push(step);
+
+        let done = match self.goal {
+            RouteGoal::Resources => {
+                let better = match &self.best {
+                    None => true,
+                    Some(best) => {
+                        route.value() > best.value()
+                            || (route.value() == best.value() && route.end() < best.end())
+                    }
+                };
+                if better {
+                    self.best = Some(route.clone());
+                }
+                false
+            }
+            RouteGoal::Level(level) if route.level() >= level => {
+                // routes taking longer were cut before reaching here
+                self.best = Some(route.clone());
+                true
+            }
+            RouteGoal::Level(_) => false,
+        };
+        if !done {
+            for after in 0..self.planner.camps.len() {
+                self.visit(after, route, available, cleared);
+            }
+        }
+
+        route.pop();
+        available[next] = respawn;
+        cleared[next] -= 1;
+    }
+}
+
+/// Time window during which the jungler of `side` can gank a lane, the wave sitting on its half
+/// of the map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GankWindow {
+    pub lane: Lane,
+    pub window: Range<GameTimer>,
+    pub side: Team,
+    /// Time for the jungler to reach the wave when the window opens, `None` if it can't.
+    pub travel: Option<GameTimer>,
+}
+
+/// Distance along the blue path of `lane` at which it crosses the river, halfway between the
+/// two nexuses.
+pub fn river_crossing(store: &EntityStore, lane: Lane) -> f32 {
+    let lanes = &store.lanes;
+    let lengths = lanes.lengths(&lanes[(Team::Blue, lane)]).unwrap();
+    let [blue, red] = [Team::Blue, Team::Red].map(|team| NexusIndex::from(team).position().point);
+
+    // the blue nexus gets further and the red one closer while walking the lane
+    let (mut from, mut to) = (0.0, lengths.length());
+    while to - from > 1.0 {
+        let half = (from + to) / 2.0;
+        let point = lengths.point_at(half).unwrap();
+        if (point - blue).length() < (point - red).length() {
+            from = half;
+        } else {
+            to = half;
+        }
+    }
+    from
+}
+
+/// Point at `distance` along the blue path of `lane`.
+fn lane_point(store: &EntityStore, lane: Lane, distance: f32) -> Option<Point> {
+    let lanes = &store.lanes;
+    lanes
+        .lengths(&lanes[(Team::Blue, lane)])?
+        .point_at(distance)
+}
+
+/// Distance along the blue path of `lane` where the waves of both teams fight, or where the only
+/// wave of the lane stands.
+pub fn wave_position(store: &EntityStore, lane: Lane) -> Option<f32> {
+    let lanes = &store.lanes;
+    let lengths = lanes.lengths(&lanes[(Team::Blue, lane)])?;
+
+    // red minions walk their own path, they are placed on the blue one at its closest point
+    let (mut blue_front, mut red_front) = (None::<f32>, None::<f32>);
+    for minion in store
+        .minions()
+        .filter(|minion| minion.guid().lane() == Some(lane))
+    {
+        match minion.team() {
+            Some(Team::Blue) => {
+                let position = minion.pathfinding_component().position;
+                blue_front = Some(blue_front.map_or(position, |f| f.max(position)))
+            }
+            Some(Team::Red) => {
+                let Some(position) = lengths.project(*minion.position()) else {
+                    continue;
+                };
+                red_front = Some(red_front.map_or(position, |f| f.min(position)))
+            }
+            None => {}
+        }
+    }
+    match (blue_front, red_front) {
+        (Some(blue_front), Some(red_front)) => Some((blue_front + red_front) / 2.0),
+        (front, None) | (None, front) => front.map(|front| front.clamp(0.0, lengths.length())),
+    }
+}
+
+/// Team whose jungler can gank `lane`: the wave sits on its half of the lane, where the enemy
+/// laner follows it, short of its turrets which the enemy laner keeps out of.
+pub fn gank_side(store: &EntityStore, lane: Lane) -> Option<Team> {
+    let position = wave_position(store, lane)?;
+    let side = if position < river_crossing(store, lane) {
+        Team::Blue
+    } else {
+        Team::Red
+    };
+
+    // a wave under the turrets of the half it sits on is out of reach of the enemy laner
+    let wave = lane_point(store, lane, position)?;
+    let guarded = store
+        .turrets()
+        .filter(|turret| turret.team() == Some(side) && turret.is_up())
+        .any(|turret| {
+            let reach = turret.get_state().base_stats().range + turret.radius();
+            (*turret.position() - wave).length() <= reach
+        });
+    (!guarded).then_some(side)
+}
+
+/// Step the engine by `step` until `until` and list the gank windows opening meanwhile, with the
+/// travel time of a jungler walking at `movespeed` from `jungler` to the wave.
+pub fn gank_windows(
+    engine: &mut MinimapEngine,
+    store: &mut EntityStore,
+    until: GameTimer,
+    step: GameTimer,
+    jungler: Point,
+    movespeed: f32,
+) -> Vec<GankWindow> {
+    const LANES: [Lane; 3] = [Lane::Top, Lane::Mid, Lane::Bot];
+
+    let mut windows = vec![];
+    let mut open: [Option<GankWindow>; 3] = Default::default();
+    while engine.timer < until && store.winner().is_none() {
+        engine.on_step(store, std::cmp::min(step, until - engine.timer));
+
+        for (lane, open) in LANES.into_iter().zip(open.iter_mut()) {
+            let side = gank_side(store, lane);
+            if open.as_ref().map(|window| window.side) == side {
+                continue;
+            }
+            if let Some(mut window) = open.take() {
+                window.window.end = engine.timer;
+                windows.push(window);
+            }
+            *open = side.map(|side| {
+                let wave = wave_position(store, lane).and_then(|at| lane_point(store, lane, at));
+                GankWindow {
+                    lane,
+                    window: engine.timer..engine.timer,
+                    side,
+                    travel: wave.and_then(|wave| {
+                        store
+                            .travel_time(
+                                jungler,
+                                &Objective::Position(wave),
+                                movespeed,
+                                ChampionBuilder::RADIUS,
+                            )
+                            .ok()
+                    }),
+                }
+            });
+        }
+    }
+    for mut window in open.into_iter().flatten() {
+        window.window.end = engine.timer;
+        windows.push(window);
+    }
+
+    windows.sort_by_key(|window| (window.lane as usize, window.window.start));
+    windows
+}
+
+#[test]
+fn jungle_routes_follow_camp_timers() {
+    use std::time::Duration;
+
+    use crate::units::champion::{ChampionStats, Role};
+
+    let seconds = |secs| GameTimer(Duration::from_secs(secs));
+    let profile = ClearProfile::new(345.0)
+        .set_clear_time(CampKind::BlueBuff, seconds(20))
+        .set_clear_time(CampKind::RedBuff, seconds(20))
+        .set_clear_time(CampKind::Gromp, seconds(15))
+        .set_clear_time(CampKind::Wolves, seconds(12))
+        .set_clear_time(CampKind::Raptors, seconds(12))
+        .set_clear_time(CampKind::Krugs, seconds(18))
+        .set_clear_time(CampKind::ScuttleTop, seconds(10))
+        .set_clear_time(CampKind::ScuttleBot, seconds(10));
+
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let start = CampKind::RedBuff.first_spawn();
+    let budget = seconds(3 * 60);
+    let (route, fastest) = {
+        let planner = RoutePlanner::new(&store, Team::Blue, profile.clone());
+
+        let route = planner
+            .plan(
+                CampIndex::BLUE_RED_BUFF,
+                start,
+                budget,
+                RouteGoal::Resources,
+            )
+            .unwrap();
+        assert_eq!(route.steps[0].camp, CampIndex::BLUE_RED_BUFF);
+
+        // every camp is taken once up, after walking from the previous one
+        let mut up = HashMap::new();
+        for (i, step) in route.steps.iter().enumerate() {
+            assert!(step.cleared <= start + budget);
+            let spawn = up
+                .get(&step.camp)
+                .copied()
+                .unwrap_or(step.camp.1.first_spawn());
+            assert_eq!(step.start, std::cmp::max(step.arrival, spawn));
+            assert_eq!(
+                step.cleared,
+                step.start + profile.clear_time(step.camp.1).unwrap()
+            );
+            if i > 0 {
+                let last = route.steps[i - 1];
+                let travel = planner.travel_time(last.camp, step.camp).unwrap();
+                assert_eq!(step.arrival, last.cleared + travel);
+            }
+            up.insert(step.camp, step.cleared + step.camp.1.respawn());
+        }
+        let camps = route.steps.iter().map(|step| step.camp.1);
+        assert_eq!(
+            route.gold,
+            camps.clone().map(|camp| camp.gold()).sum::<usize>()
+        );
+        assert_eq!(
+            route.experience,
+            camps.map(|camp| camp.experience()).sum::<f32>()
+        );
+
+        // the planned route is at least as good as the usual full clear
+        let full_clear = planner
+            .evaluate(
+                &[
+                    CampIndex::BLUE_RED_BUFF,
+                    CampIndex::BLUE_KRUGS,
+                    CampIndex::BLUE_RAPTORS,
+                    CampIndex::BLUE_WOLVES,
+                    CampIndex::BLUE_BLUE_BUFF,
+                    CampIndex::BLUE_GROMP,
+                ],
+                start,
+            )
+            .unwrap();
+        assert!(full_clear.end().unwrap() <= start + budget);
+        assert!(route.value() >= full_clear.value());
+
+        // reaching level 3 takes no camp more than needed
+        let fastest = planner
+            .plan(CampIndex::BLUE_RED_BUFF, start, budget, RouteGoal::Level(3))
+            .unwrap();
+        assert!(fastest.level() >= 3);
+        let mut shorter = fastest.clone();
+        shorter.pop();
+        assert!(shorter.level() < 3);
+        (route, fastest)
+    };
+    assert!(fastest.end() <= route.end());
+
+    // the route plays out in the engine
+    let stats =
+        ChampionStats::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/champions.json")).unwrap();
+    let red_buff = *store.get_camp(CampIndex::BLUE_RED_BUFF).unwrap().position();
+    let champion = store.spawn(
+        ChampionBuilder::new("Lee Sin", stats["Lee Sin"])
+            .set_team(Team::Blue)
+            .set_role(Role::Jungle)
+            .set_movespeed(profile.movespeed)
+            .set_position(red_buff),
+    );
+    engine.on_step(&mut store, start);
+    route.replay(&mut engine, &mut store, champion);
+
+    assert_eq!(engine.timer, route.end().unwrap());
+    assert_eq!(store.gold(champion), route.gold as f32);
+    assert_eq!(
+        store.get_champion(champion).unwrap().get_state().level(),
+        route.level()
+    );
+    let last = route.steps.last().unwrap();
+    let camp = store.get_camp(last.camp).unwrap();
+    assert_eq!(
+        camp.get_state().next_spawn(),
+        Some(last.cleared + last.camp.1.respawn())
+    );
+    let distance = (*store.get_champion(champion).unwrap().position() - *camp.position()).length();
+    assert!(
+        distance < camp.radius() + ChampionBuilder::RADIUS + 1.0,
+        "{distance}"
+    );
+}
+
+#[test]
+fn planned_routes_take_camps_again_once_they_respawn() {
+    let seconds = |secs| GameTimer(std::time::Duration::from_secs(secs));
+    let profile = ClearProfile::new(345.0)
+        .set_clear_time(CampKind::Gromp, seconds(12))
+        .set_clear_time(CampKind::BlueBuff, seconds(12))
+        .set_clear_time(CampKind::Wolves, seconds(12));
+
+    // every camp is up at 10:00, gromp and wolves respawn before the end of the budget
+    let (_, store) = crate::MinimapEngine::init();
+    let camps = [
+        CampIndex::BLUE_GROMP,
+        CampIndex::BLUE_BLUE_BUFF,
+        CampIndex::BLUE_WOLVES,
+    ];
+    let planner = RoutePlanner::with_camps(&store, camps, profile);
+    let start = GameTimer::MINUTES_10;
+    let budget = seconds(3 * 60);
+    let route = planner
+        .plan(CampIndex::BLUE_GROMP, start, budget, RouteGoal::Resources)
+        .unwrap();
+
+    // some camp is taken again once it respawned
+    let again = camps
+        .iter()
+        .any(|camp| route.steps.iter().filter(|step| step.camp == *camp).count() > 1);
+    assert!(again, "{route:?}");
+
+    // no order of the camps does better, whatever their respawn timers
+    fn best(
+        planner: &RoutePlanner,
+        order: &mut Vec<CampIndex>,
+        camps: &[CampIndex],
+        start: GameTimer,
+        deadline: GameTimer,
+    ) -> f32 {
+        let Some(route) = planner.evaluate(order, start) else {
+            return 0.0;
+        };
+        if route.end().is_some_and(|end| end > deadline) {
+            return 0.0;
+        }
+        let mut value = route.value();
+        for camp in camps {
+            order.push(*camp);
+            value = value.max(best(planner, order, camps, start, deadline));
+            order.pop();
+        }
+        value
+    }
+    let mut order = vec![CampIndex::BLUE_GROMP];
+    let exhaustive = best(&planner, &mut order, &camps, start, start + budget);
+    assert_eq!(route.value(), exhaustive);
+}
+
+#[test]
+fn camp_clears_depend_on_champion_stats() {
+    use crate::units::champion::ChampionStats;
+
+    let stats =
+        ChampionStats::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/champions.json")).unwrap();
+    let lee = stats["Lee Sin"].at_level(1);
+
+    // gromp has no armor, every attack deals the attack damage of the champion
+    let gromp = clear_camp(&lee, &CampKind::Gromp.monsters(), false).unwrap();
+    let attacks = (2050.0 / lee.attack_damage).ceil();
+    let expected = (attacks - 1.0) / lee.attack_speed;
+    assert!((gromp.time.as_secs_f32() - expected).abs() < 1e-3);
+    assert!(gromp.health_lost > 0.0);
+
+    let smitten = clear_camp(&lee, &CampKind::Gromp.monsters(), true).unwrap();
+    assert!(smitten.time < gromp.time);
+    assert!(smitten.health_lost < gromp.health_lost);
+
+    // penetration only matters against armored monsters
+    let raptors = clear_camp(&lee, &CampKind::Raptors.monsters(), false).unwrap();
+    let penetrating = UnitStatistics {
+        armor_penetration: 0.5,
+        ..lee
+    };
+    assert!(
+        clear_camp(&penetrating, &CampKind::Raptors.monsters(), false)
+            .unwrap()
+            .time
+            < raptors.time
+    );
+    assert_eq!(
+        clear_camp(&penetrating, &CampKind::Gromp.monsters(), false),
+        Some(gromp)
+    );
+
+    // lifesteal and armor keep the champion healthier
+    let lifesteal = UnitStatistics {
+        lifesteal: 0.1,
+        ..lee
+    };
+    let armored = UnitStatistics {
+        armor: lee.armor + 50.0,
+        ..lee
+    };
+    for sturdier in [lifesteal, armored] {
+        let clear = clear_camp(&sturdier, &CampKind::Wolves.monsters(), false).unwrap();
+        let wolves = clear_camp(&lee, &CampKind::Wolves.monsters(), false).unwrap();
+        assert_eq!(clear.time, wolves.time);
+        assert!(clear.health_lost < wolves.health_lost);
+    }
+
+    // the scuttle crab does not fight back, negative magic resist amplifies magic damage
+    let scuttle = clear_camp(&lee, &CampKind::ScuttleTop.monsters(), false).unwrap();
+    assert_eq!(scuttle.health_lost, 0.0);
+    assert!(Damage::Magic(100.0).against(&CampKind::ScuttleTop.monsters()[0]) > 100.0);
+    assert!(clear_camp(
+        &UnitStatistics::default(),
+        &CampKind::Gromp.monsters(),
+        false
+    )
+    .is_none());
+
+    let profile = ClearProfile::from_stats(&lee, true);
+    assert_eq!(profile.movespeed, lee.movespeed);
+    assert_eq!(profile.clear_time(CampKind::Gromp), Some(smitten.time));
+    assert!(CampKind::ALL
+        .iter()
+        .all(|kind| profile.clear_time(*kind).is_some()));
+
+    // camps in the store estimate their clear from their monsters
+    let (_, store) = crate::MinimapEngine::init();
+    let camp = store.get_camp(CampIndex::RED_GROMP).unwrap();
+    assert_eq!(camp.clear_by(&lee, false), Some(gromp));
+}
+
+#[test]
+fn gank_windows_follow_the_waves() {
+    let (mut engine, mut store) = crate::MinimapEngine::init();
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    engine.on_step(&mut store, GameTimer::FIRST_SPAWN + second);
+
+    // without the first red wave, the blue one keeps walking towards the red side
+    let red_bot = store
+        .minions()
+        .filter(|minion| {
+            minion.team() == Some(Team::Red) && minion.guid().lane() == Some(Lane::Bot)
+        })
+        .map(|minion| minion.guid())
+        .collect::<Vec<_>>();
+    for minion in red_bot {
+        store.remove_by_id(minion).unwrap();
+    }
+
+    // the blue wave still stands under the turrets of its base
+    assert_eq!(gank_side(&store, Lane::Bot), None);
+
+    let raptors = *store.get_camp(CampIndex::BLUE_RAPTORS).unwrap().position();
+    let next_wave = GameTimer::FIRST_SPAWN + GameTimer::WAVE_PERIOD;
+    let until = next_wave + GameTimer(std::time::Duration::from_secs(10));
+    let windows = gank_windows(&mut engine, &mut store, until, second, raptors, 345.0);
+    assert_eq!(engine.timer, until);
+
+    for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+        let lane = windows
+            .iter()
+            .filter(|window| window.lane == lane)
+            .collect::<Vec<_>>();
+        assert!(!lane.is_empty());
+        for window in &lane {
+            assert!(window.window.start <= window.window.end);
+            assert!(window.travel.is_some());
+        }
+        for [before, after] in lane.array_windows() {
+            assert!(before.window.end <= after.window.start);
+        }
+    }
+
+    // the blue wave is far enough when the second waves spawn to meet the red one on its side,
+    // its turrets close the window while it walks past them
+    let bot = windows
+        .iter()
+        .filter(|window| window.lane == Lane::Bot)
+        .collect::<Vec<_>>();
+    let (red, blue) = bot.split_last().unwrap();
+    assert!(blue.len() > 1);
+    assert!(blue.iter().all(|window| window.side == Team::Blue));
+    assert_eq!(red.side, Team::Red);
+    assert!(next_wave <= red.window.start && red.window.start <= next_wave + second);
+    assert!(wave_position(&store, Lane::Bot).unwrap() > river_crossing(&store, Lane::Bot));
+
+    // the closer lane is reached sooner by the jungler standing at its raptors
+    let top = windows
+        .iter()
+        .find(|window| window.lane == Lane::Top)
+        .unwrap();
+    assert!(bot[0].travel < top.travel);
+}
@@ -2,23 +2,26 @@
 #![feature(stmt_expr_attributes)]
 #![feature(lazy_cell)]
 #![feature(array_windows)]
-#![feature(iter_array_chunks)]
 
 pub mod ecs;
 
 pub mod core;
+pub mod jungle;
 pub mod nav_engine;
 pub mod stats;
 
+use std::collections::HashMap;
+
 use crate::{
     core::{GameTimer, Lane, Team},
     ecs::{
-        entity::EntityMut,
+        entity::{EntityMut, EntityRef},
         generic::pathfinding::PathfindError,
         spawners::wave::WaveBuilder,
         structures::{self, inhibitor, nexus, turret},
-        units,
+        units::{self, camp, monster},
     },
+    stats::GoldCollectable,
 };
 
 pub trait Engine {
@@ -28,6 +31,22 @@ pub trait Engine {
 
 pub struct MinimapEngine {
     pub timer: GameTimer,
+    /// Gold and experience of the minions that spawned in each lane.
+    pub(crate) resources: HashMap<Lane, LaneResources>,
+}
+
+/// Gold and experience brought into a lane by both teams' minions.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LaneResources {
+    pub gold: usize,
+    pub experience: f32,
+}
+
+/// Point of a lane where two opposing waves first meet, and when they do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveMeeting {
+    pub point: lyon::math::Point,
+    pub timer: GameTimer,
 }
 
 impl Engine for MinimapEngine {
@@ -73,89 +92,240 @@ impl Engine for MinimapEngine {
 
         builder.spawn(nexus::NexusIndex::from(Team::Blue));
         builder.spawn(nexus::NexusIndex::from(Team::Red));
+
+        for camp in camp::CampIndex::ALL {
+            builder.spawn(camp);
+        }
+        for monster in monster::MonsterKind::ALL {
+            builder.spawn(monster::MonsterIndex(monster));
+        }
     }
 
     fn on_step(&mut self, store: &mut crate::ecs::store::EntityStore, step: GameTimer) {
+        // the game is over once a nexus is destroyed
+        if store.winner().is_some() {
+            return;
+        }
+
         let new_timer = self.timer + step;
 
-        // pathfind existing minions
-        for minion in store.minions_mut() {
-            match minion.pathfind_for_duration(step) {
-                Ok(_) => {}
-                Err(PathfindError::EndReached(_)) => {
-                    minion.delete().map(drop).expect("can't delete minion")
-                }
+        // pathfind units following their objectives
+        for unit in store.units_mut().filter(|unit| !unit.entity().is_minion()) {
+            match unit.pathfind_for_duration(step) {
+                Ok(_) | Err(PathfindError::EndReached(_)) => {}
             }
         }
 
-        // spawn new minions
-        for spawn_timer in ecs::spawners::wave::timer_to_wave_spawn(self.timer, new_timer) {
-            for (team, lane) in [
-                (Team::Blue, Lane::Top),
-                (Team::Blue, Lane::Mid),
-                (Team::Blue, Lane::Bot),
-                (Team::Red, Lane::Top),
-                (Team::Red, Lane::Mid),
-                (Team::Red, Lane::Bot),
-            ] {
-                let mut wave = WaveBuilder::default()
-                    .set_lane(lane)
-                    .set_team(team)
-                    .has_siege(ecs::spawners::wave::has_siege(spawn_timer))
-                    .set_super([
-                        store
-                            .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
-                                team.opposite(),
-                                Lane::Top,
-                            ))
-                            .unwrap(),
-                        store
-                            .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
-                                team.opposite(),
-                                Lane::Mid,
-                            ))
-                            .unwrap(),
-                        store
-                            .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
-                                team.opposite(),
-                                Lane::Bot,
-                            ))
-                            .unwrap(),
-                    ]);
-                while let Some(minion) = ecs::generic::spawner::EntitySpawner::spawn_next(&mut wave)
-                {
-                    let id = store.spawn(minion);
-
-                    let minion = store
-                        .get_minion_mut(id)
-                        .expect("minion should have spawned");
-
-                    // pathfind minions from `spawn_timer` to `new_timer`
-                    match minion.pathfind_for_duration(new_timer - spawn_timer) {
-                        Ok(_) => {}
-                        Err(PathfindError::EndReached(_)) => {
-                            minion.delete().map(drop).expect("can't delete minion")
-                        }
+        // minions move in short ticks so that opposing waves meet instead of walking through
+        // each other during long steps
+        let mut elapsed = GameTimer::GAME_START;
+        while elapsed < step {
+            let before = self.timer + elapsed;
+            let mut tick = std::cmp::min(GameTimer::COMBAT_TICK, step - elapsed);
+
+            // ticks are cut at wave spawns, so that new waves spawn at the start of a tick and
+            // move, fight and get shot like every other minion
+            for spawn_timer in ecs::spawners::wave::timer_to_wave_spawn(before, before + tick) {
+                if spawn_timer > before {
+                    tick = spawn_timer - before;
+                    break;
+                }
+                self.spawn_waves(store, spawn_timer);
+            }
+            elapsed = elapsed + tick;
+
+            // the remaining plates fall off the outer turrets at 14:00
+            if before < GameTimer::MINUTES_14 && self.timer + elapsed >= GameTimer::MINUTES_14 {
+                turret::remove_plates(store);
+            }
+
+            // turrets shoot before minions fight, at the game time reached during this tick
+            turret::shoot(store, self.timer + elapsed, tick);
+
+            // minions with an enemy in range stand still and fight
+            let fighting = units::minion::fight(store, self.timer + elapsed, tick);
+
+            // the game stops at the time the nexus falls, no more waves spawn after it
+            if store.winner().is_some() {
+                self.timer = self.timer + elapsed;
+                return;
+            }
+
+            // pathfind existing minions, at their movespeed for the current game time
+            units::minion::update_movespeed(store, self.timer + elapsed);
+            for minion in store
+                .minions_mut()
+                .filter(|minion| !fighting.contains(&minion.guid()))
+            {
+                match minion.pathfind_for_duration(tick) {
+                    Ok(_) => {}
+                    Err(PathfindError::EndReached(_)) => {
+                        minion.delete().map(drop).expect("can't delete minion")
                     }
                 }
             }
         }
 
+        // inhibitors come back up once their respawn timer is over, after the waves spawned
+        // during this step have checked them
+        inhibitor::respawn(store, new_timer);
+
+        // jungle camps spawn once their first spawn or respawn timer is over
+        camp::respawn(store, new_timer);
+        monster::update(store, new_timer);
+
         self.timer = new_timer;
     }
 }
 
+impl Default for MinimapEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MinimapEngine {
+    /// Engine at the start of the game, before any wave spawned.
+    pub fn new() -> Self {
+        MinimapEngine {
+            timer: GameTimer::GAME_START,
+            resources: HashMap::new(),
+        }
+    }
+
     pub fn init() -> (Self, ecs::store::EntityStore) {
         let mut store = ecs::builder::EntityStoreBuilder::new();
-        let mut engine = MinimapEngine {
-            timer: GameTimer::GAME_START,
-        };
+        let mut engine = MinimapEngine::new();
         engine.on_start(&mut store);
     
         let store = store.build();
         (engine, store)
     }
+
+    /// Spawn the waves of both teams in every lane at `spawn_timer`.
+    fn spawn_waves(&mut self, store: &mut ecs::store::EntityStore, spawn_timer: GameTimer) {
+        for (team, lane) in [
+            (Team::Blue, Lane::Top),
+            (Team::Blue, Lane::Mid),
+            (Team::Blue, Lane::Bot),
+            (Team::Red, Lane::Top),
+            (Team::Red, Lane::Mid),
+            (Team::Red, Lane::Bot),
+        ] {
+            let mut wave = WaveBuilder::default()
+                .set_lane(lane)
+                .set_team(team)
+                .set_path(std::sync::Arc::clone(&store.lanes[(team, lane)]))
+                .set_spawn(spawn_timer)
+                .has_siege(ecs::spawners::wave::has_siege(spawn_timer))
+                .set_super([
+                    store
+                        .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
+                            team.opposite(),
+                            Lane::Top,
+                        ))
+                        .unwrap(),
+                    store
+                        .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
+                            team.opposite(),
+                            Lane::Mid,
+                        ))
+                        .unwrap(),
+                    store
+                        .get_inhib(ecs::structures::inhibitor::InhibitorIndex(
+                            team.opposite(),
+                            Lane::Bot,
+                        ))
+                        .unwrap(),
+                ]);
+            while let Some(minion) = ecs::generic::spawner::EntitySpawner::spawn_next(&mut wave) {
+                let id = store.spawn(minion);
+
+                let spawned = store.get_minion(id).expect("minion should have spawned");
+                let resources = self.resources.entry(lane).or_default();
+                resources.gold += spawned.get_state().golds();
+                resources.experience += spawned.get_state().experience();
+            }
+        }
+    }
+
+    /// Gold and experience that entered `lane` so far with the minions of both teams.
+    pub fn lane_resources(&self, lane: Lane) -> LaneResources {
+        self.resources.get(&lane).copied().unwrap_or_default()
+    }
+
+    /// Where and when the two waves spawning as the `wave_number`th wave of the game first meet
+    /// in `lane`, computed from their spawn time and movespeed without stepping the game. Both
+    /// fronts walk their own team's path at the same speed, they meet halfway between each other
+    /// once their front melee minions are within attack range.
+    pub fn predict_wave_meeting(
+        &self,
+        store: &ecs::store::EntityStore,
+        lane: Lane,
+        wave_number: usize,
+    ) -> Option<WaveMeeting> {
+        use ecs::entity::EntityBuilder;
+        use stats::WithUnitStats;
+        use units::minion::{MinionBuilder, MinionComponent, MinionType};
+
+        let spawn = ecs::spawners::wave::wave_spawn(wave_number);
+        let blue = store.lanes.lengths(&store.lanes[(Team::Blue, lane)])?;
+        let red = store.lanes.lengths(&store.lanes[(Team::Red, lane)])?;
+        let front = MinionComponent::new(MinionType::Melee, spawn);
+        let radius = MinionBuilder::melee()
+            .set_team(Team::Blue)
+            .set_lane(lane)
+            .set_path(std::sync::Arc::clone(&store.lanes[(Team::Blue, lane)]))
+            .position()
+            .radius;
+        let reach = front.base_stats().range + 2.0 * radius;
+
+        // distance between the fronts once each walked `walked` along its path, center to center
+        let fronts = |walked: f32| Some((blue.point_at(walked)?, red.point_at(walked)?));
+        let apart = |walked: f32| fronts(walked).map(|(blue, red)| (blue - red).length());
+
+        // walk the fronts in short steps until they are in range, then narrow it down
+        const STEP: f32 = 25.0;
+        let mut from = 0.0;
+        while apart(from + STEP)? > reach {
+            from += STEP;
+            if from > blue.length().min(red.length()) {
+                return None;
+            }
+        }
+        let mut to = from + STEP;
+        while to - from > 0.01 {
+            let half = (from + to) / 2.0;
+            if apart(half)? > reach {
+                from = half;
+            } else {
+                to = half;
+            }
+        }
+        let (blue_front, red_front) = fronts(to)?;
+
+        // time to walk that far, with the movespeed upgrades on the way
+        let mut left = to;
+        let mut timer = spawn;
+        for breakpoint in MinionComponent::MOVESPEED_BREAKPOINTS {
+            if breakpoint <= timer {
+                continue;
+            }
+            let movespeed = front.current_stats(&timer).movespeed;
+            let walked = (breakpoint - timer).as_secs_f32() * movespeed;
+            if walked >= left {
+                break;
+            }
+            left -= walked;
+            timer = breakpoint;
+        }
+        let movespeed = front.current_stats(&timer).movespeed;
+
+        Some(WaveMeeting {
+            point: blue_front.lerp(red_front, 0.5),
+            timer: timer + GameTimer(std::time::Duration::from_secs_f32(left / movespeed)),
+        })
+    }
 }
 
 #[test]
@@ -165,9 +335,7 @@ fn run_engine() {
     use std::time::Duration;
 
     let mut store = EntityStoreBuilder::new();
-    let mut engine = MinimapEngine {
-        timer: GameTimer::GAME_START,
-    };
+    let mut engine = MinimapEngine::new();
     engine.on_start(&mut store);
 
     let mut store = store.build();
@@ -216,3 +384,143 @@ fn run_engine() {
 
     dbg!(&removed_after[0]);
 }
+
+#[test]
+fn lane_resources_add_up_spawned_waves() {
+    use crate::ecs::units::minion::MinionType;
+
+    let (mut engine, mut store) = MinimapEngine::init();
+    assert_eq!(engine.lane_resources(Lane::Mid), LaneResources::default());
+
+    // the first two waves have 3 melee and 3 ranged minions, the third one a siege minion too
+    let wave = 3 * (MinionType::Melee.gold(0) + MinionType::Ranged.gold(0));
+    let experience = 3.0 * (MinionType::Melee.experience() + MinionType::Ranged.experience());
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    engine.on_step(
+        &mut store,
+        GameTimer::FIRST_SPAWN + GameTimer::WAVE_PERIOD + second,
+    );
+    for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+        let resources = engine.lane_resources(lane);
+        assert_eq!(resources.gold, 2 * 2 * wave);
+        assert!((resources.experience - 2.0 * 2.0 * experience).abs() < 0.01);
+    }
+
+    engine.on_step(&mut store, GameTimer::WAVE_PERIOD);
+    let siege = MinionType::Siege.gold(0);
+    assert_eq!(
+        engine.lane_resources(Lane::Bot).gold,
+        2 * (3 * wave + siege)
+    );
+}
+
+#[test]
+fn waves_spawned_during_a_long_step_fight() {
+    use ecs::{
+        entity::{EntityRef, EntityRefCrateExt},
+        spawners::wave::wave_spawn,
+    };
+    use stats::WithUnitStats;
+
+    // a single step spawns the first waves and goes on past the time they meet
+    let (mut engine, mut store) = MinimapEngine::init();
+    let met = [Lane::Top, Lane::Mid, Lane::Bot]
+        .map(|lane| engine.predict_wave_meeting(&store, lane, 1).unwrap().timer)
+        .into_iter()
+        .max()
+        .unwrap();
+    engine.on_step(
+        &mut store,
+        met + GameTimer(std::time::Duration::from_secs(5)),
+    );
+
+    for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+        let minions = || {
+            store
+                .minions()
+                .filter(move |minion| minion.guid().lane() == Some(lane))
+        };
+        assert!(minions().any(|minion| {
+            let state = minion.get_state();
+            state.health < state.current_stats(&wave_spawn(1)).health
+        }));
+
+        // the waves stopped where they met instead of walking through each other
+        let lengths = store
+            .lanes
+            .lengths(&store.lanes[(Team::Blue, lane)])
+            .unwrap();
+        let walked = |team: Team| {
+            minions()
+                .filter(|minion| minion.team() == Some(team))
+                .map(|minion| minion.pathfinding_component().position)
+                .max_by(f32::total_cmp)
+                .unwrap()
+        };
+        assert!(
+            walked(Team::Blue) <= lengths.length() - walked(Team::Red),
+            "{lane:?}"
+        );
+    }
+}
+
+#[test]
+fn predicted_wave_meetings_match_the_simulation() {
+    use ecs::{entity::EntityRef, spawners::wave::wave_spawn};
+
+    // step the game until the opposing minions of each lane first have a target, and compare
+    // where and when it happens to the prediction
+    let meetings = |engine: &mut MinimapEngine, store: &mut ecs::store::EntityStore, wave| {
+        let mut met = HashMap::new();
+        while met.len() < 3 {
+            engine.on_step(store, GameTimer::COMBAT_TICK);
+            for lane in [Lane::Top, Lane::Mid, Lane::Bot] {
+                let front = |team: Team| {
+                    store
+                        .minions()
+                        .filter(|minion| minion.guid().lane() == Some(lane))
+                        .filter(|minion| minion.team() == Some(team))
+                        .find(|minion| minion.target(engine.timer).is_some())
+                        .map(|minion| *minion.position())
+                };
+                if let (Some(blue), Some(red)) = (front(Team::Blue), front(Team::Red)) {
+                    met.entry(lane)
+                        .or_insert((blue.lerp(red, 0.5), engine.timer));
+                }
+            }
+        }
+        for (lane, (point, timer)) in met {
+            let predicted = engine.predict_wave_meeting(store, lane, wave).unwrap();
+            let late = (timer.as_secs_f32() - predicted.timer.as_secs_f32()).abs();
+            assert!(
+                late <= GameTimer::COMBAT_TICK.as_secs_f32(),
+                "{lane:?} {late}"
+            );
+            let away = (point - predicted.point).length();
+            assert!(away < 10.0, "{lane:?} {away}");
+        }
+    };
+
+    let (mut engine, mut store) = MinimapEngine::init();
+    meetings(&mut engine, &mut store, 1);
+
+    // the mid lane waves meet about when they are expected to
+    let mid = engine.predict_wave_meeting(&store, Lane::Mid, 1).unwrap();
+    let travel = mid.timer - wave_spawn(1);
+    let second = GameTimer(std::time::Duration::from_secs(1));
+    assert!(travel < GameTimer::WAVE_TRAVEL + second && GameTimer::WAVE_TRAVEL < travel + second);
+
+    // waves spawning right before a breakpoint walk faster for the end of their way
+    let (mut engine, mut store) = MinimapEngine::init();
+    engine.timer = wave_spawn(18) - GameTimer::COMBAT_TICK;
+    meetings(&mut engine, &mut store, 18);
+    let travel = |wave| {
+        let meeting = engine
+            .predict_wave_meeting(&store, Lane::Bot, wave)
+            .unwrap();
+        meeting.timer - wave_spawn(wave)
+    };
+    assert!(wave_spawn(18) < GameTimer::MINUTES_10);
+    assert!(travel(18) < travel(1));
+    assert_eq!(travel(20), travel(21));
+}
@@ -0,0 +1,246 @@
+use spade::{
+    handles::{FixedDirectedEdgeHandle, FixedFaceHandle, InnerTag},
+    Point2, Triangulation,
+};
+
+use super::NavigationMap;
+
+pub type FaceId = FixedFaceHandle<InnerTag>;
+
+/// Distance between two candidates when moving portal endpoints away from walls.
+const PORTAL_STEP: f64 = 4.0;
+
+/// Number of times a path segment may be split to keep it away from walls.
+const CLEARANCE_SPLITS: usize = 8;
+
+/// Samples taken along a path segment when looking for its closest point to a wall.
+const CLEARANCE_SAMPLES: usize = 32;
+
+fn cross(apex: Point2<f64>, a: Point2<f64>, b: Point2<f64>) -> f64 {
+    (a.x - apex.x) * (b.y - apex.y) - (a.y - apex.y) * (b.x - apex.x)
+}
+
+fn distance(a: Point2<f64>, b: Point2<f64>) -> f64 {
+    ((a.x - b.x) * (a.x - b.x) + (a.y - b.y) * (a.y - b.y)).sqrt()
+}
+
+impl NavigationMap {
+    /// Walkable face containing `point`, points on an edge or a vertex resolve to any walkable
+    /// face touching them.
+    pub fn walkable_face(&self, point: Point2<f64>) -> Option<FaceId> {
+        let cdt = &self.triangulation.cdt;
+        let walkable =
+            |face: Option<FaceId>| face.filter(|f| !self.triangulation.excluded.contains(f));
+
+        match cdt.locate(point) {
+            spade::PositionInTriangulation::OnFace(face) => walkable(Some(face)),
+            spade::PositionInTriangulation::OnEdge(edge) => {
+                let edge = cdt.directed_edge(edge);
+                walkable(edge.face().fix().as_inner())
+                    .or_else(|| walkable(edge.rev().face().fix().as_inner()))
+            }
+            spade::PositionInTriangulation::OnVertex(vertex) => cdt
+                .vertex(vertex)
+                .out_edges()
+                .find_map(|edge| walkable(edge.face().fix().as_inner())),
+            _ => None,
+        }
+    }
+
+    /// Walkable face closest to `point`, looked up among all the faces when it is not walkable.
+    pub(crate) fn closest_walkable_face(&self, point: Point2<f64>) -> Option<FaceId> {
+        self.walkable_face(point).or_else(|| {
+            self.triangulation
+                .cdt
+                .inner_faces()
+                .filter(|face| !self.triangulation.excluded.contains(&face.fix()))
+                .min_by(|a, b| a.distance_2(point).total_cmp(&b.distance_2(point)))
+                .map(|face| face.fix())
+        })
+    }
+
+    /// Walkable faces sharing a non constraint edge with `face` that an agent of `radius` can
+    /// cross, along with the shared edge directed so that `face` is on its left. Constraint edges
+    /// are walls, even for agents without a radius.
+    pub(crate) fn walkable_neighbours(
+        &self,
+        face: FaceId,
+        radius: f32,
+    ) -> impl Iterator<Item = (FaceId, FixedDirectedEdgeHandle)> + '_ {
+        self.triangulation
+            .cdt
+            .face(face)
+            .adjacent_edges()
+            .into_iter()
+            .filter(|edge| !edge.is_constraint_edge())
+            .filter(move |edge| self.clearance[edge.fix().as_undirected().index()] >= radius)
+            .filter_map(|edge| {
+                let neighbour = edge.rev().face().fix().as_inner()?;
+                (!self.triangulation.excluded.contains(&neighbour)).then(|| (neighbour, edge.fix()))
+            })
+    }
+
+    /// Endpoints of the edge crossed when moving from `from` to `to` as `(left, right)`, moved
+    /// towards each other until they are `radius` away from the walls.
+    fn portal(&self, from: FaceId, to: FaceId, radius: f32) -> Option<[Point2<f64>; 2]> {
+        let edge = self
+            .walkable_neighbours(from, 0.0)
+            .find_map(|(next, edge)| (next == to).then_some(edge))?;
+        let edge = self.triangulation.cdt.directed_edge(edge);
+
+        let (left, right) = (edge.to().position(), edge.from().position());
+        if radius <= 0.0 {
+            return Some([left, right]);
+        }
+
+        let length = distance(left, right);
+        let lerp = |from: Point2<f64>, to: Point2<f64>, d: f64| {
+            let t = (d / length).min(1.0);
+            Point2::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t)
+        };
+        let shrink = |from: Point2<f64>, to: Point2<f64>| {
+            let mut d = 0.0;
+            while d < length && self.wall_distance(lerp(from, to, d).into()) < radius as f64 {
+                d += PORTAL_STEP;
+            }
+            d
+        };
+
+        let (from_left, from_right) = (shrink(left, right), shrink(right, left));
+        if from_left + from_right >= length {
+            // only a narrow part of the edge is clear, go through it
+            let clear = lerp(left, right, from_left);
+            return Some([clear, clear]);
+        }
+        Some([lerp(left, right, from_left), lerp(right, left, from_right)])
+    }
+
+    /// Shortest path from `from` to `to` staying inside `channel` and `radius` away from walls,
+    /// computed with the simple stupid funnel algorithm. Both points are expected to lie in the
+    /// first and last faces.
+    pub fn string_pull(
+        &self,
+        from: Point2<f64>,
+        to: Point2<f64>,
+        channel: &[FaceId],
+        radius: f32,
+    ) -> Vec<Point2<f64>> {
+        let portals = std::iter::once([from, from])
+            .chain(
+                channel
+                    .array_windows()
+                    .filter_map(|[a, b]| self.portal(*a, *b, radius)),
+            )
+            .chain(Some([to, to]))
+            .collect::<Vec<_>>();
+
+        let mut path = vec![from];
+        let (mut apex, mut left, mut right) = (from, from, from);
+        let (mut left_index, mut right_index) = (0, 0);
+
+        let mut i = 1;
+        while i < portals.len() {
+            let [l, r] = portals[i];
+
+            // tighten the funnel from the right
+            if cross(apex, right, r) >= 0.0 {
+                if apex == right || cross(apex, left, r) < 0.0 {
+                    right = r;
+                    right_index = i;
+                } else {
+                    // right crossed over left, left becomes the new apex
+                    path.push(left);
+                    apex = left;
+                    right = apex;
+                    right_index = left_index;
+                    i = left_index + 1;
+                    continue;
+                }
+            }
+
+            // tighten the funnel from the left
+            if cross(apex, left, l) <= 0.0 {
+                if apex == left || cross(apex, right, l) > 0.0 {
+                    left = l;
+                    left_index = i;
+                } else {
+                    // left crossed over right, right becomes the new apex
+                    path.push(right);
+                    apex = right;
+                    left = apex;
+                    left_index = right_index;
+                    i = right_index + 1;
+                    continue;
+                }
+            }
+
+            i += 1;
+        }
+
+        if path.last() != Some(&to) {
+            path.push(to);
+        }
+
+        if radius <= 0.0 {
+            return path;
+        }
+
+        let mut cleared = vec![from];
+        for [a, b] in path.array_windows() {
+            self.keep_clear(*a, *b, radius as f64, CLEARANCE_SPLITS, &mut cleared);
+        }
+        cleared
+    }
+
+    /// Push the segment `a -> b` (excluding `a`) to `out`, splitting it at points moved away from
+    /// the closest wall when it cuts a corner closer than `radius`.
+    fn keep_clear(
+        &self,
+        a: Point2<f64>,
+        b: Point2<f64>,
+        radius: f64,
+        splits: usize,
+        out: &mut Vec<Point2<f64>>,
+    ) {
+        // endpoints closer to a wall than the radius (targets next to walls) lower the requirement
+        let required = radius
+            .min(self.wall_distance(a.into()))
+            .min(self.wall_distance(b.into()))
+            - 1.0;
+
+        let closest = (1..CLEARANCE_SAMPLES)
+            .map(|i| {
+                let t = i as f64 / CLEARANCE_SAMPLES as f64;
+                Point2::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+            })
+            .map(|p| (p, self.wall_distance(p.into())))
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+
+        let split = closest
+            .filter(|(_, d)| splits > 0 && *d < required && *d > 0.0)
+            .and_then(|(p, d)| {
+                let wall = self
+                    .walls
+                    .nearest_neighbor(&p.into())?
+                    .nearest_point(&p.into());
+                let scale = (radius + 1.0) / d;
+                let moved = Point2::new(
+                    wall[0] + (p.x - wall[0]) * scale,
+                    wall[1] + (p.y - wall[1]) * scale,
+                );
+                let cdt = &self.triangulation.cdt;
+                (self.walkable_face(moved).is_some()
+                    && !cdt.intersects_constraint(a, moved)
+                    && !cdt.intersects_constraint(moved, b))
+                .then_some(moved)
+            });
+
+        match split {
+            Some(moved) => {
+                self.keep_clear(a, moved, radius, splits - 1, out);
+                self.keep_clear(moved, b, radius, splits - 1, out);
+            }
+            None => out.push(b),
+        }
+    }
+}
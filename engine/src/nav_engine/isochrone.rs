@@ -0,0 +1,112 @@
+use geo::BooleanOps;
+use spade::{Point2, Triangulation};
+
+use super::{FaceId, NavigationMap};
+
+/// Number of sides of the polygon approximating the area reached from a face.
+const CIRCLE_SIDES: usize = 32;
+
+fn circle(center: Point2<f64>, radius: f64) -> geo::Polygon<f64> {
+    let exterior = (0..CIRCLE_SIDES)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::TAU / CIRCLE_SIDES as f64;
+            geo::coord! { x: center.x + radius * angle.cos(), y: center.y + radius * angle.sin() }
+        })
+        .collect::<Vec<_>>();
+    geo::Polygon::new(exterior.into(), vec![])
+}
+
+impl NavigationMap {
+    /// Region an agent of `radius` standing at `from` can reach by walking at most `distance`,
+    /// made of the reachable part of every navmesh face.
+    pub fn isochrone(
+        &self,
+        from: Point2<f64>,
+        distance: f64,
+        radius: f32,
+    ) -> geo::MultiPolygon<f64> {
+        let mut region = geo::MultiPolygon::new(vec![]);
+        let Some(start) = self.walkable_face(from) else {
+            return region;
+        };
+
+        let waypoint = |face: FaceId| match face == start {
+            true => from,
+            false => self.triangulation.cdt.face(face).center(),
+        };
+        let length =
+            |a: Point2<f64>, b: Point2<f64>| ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt();
+
+        // distances are rounded up to whole game units
+        let reached = pathfinding::prelude::dijkstra_reach(&start, |face| {
+            let from = waypoint(*face);
+            self.walkable_neighbours(*face, radius)
+                .map(move |(next, _)| (next, length(from, waypoint(next)).ceil() as i64))
+                .collect::<Vec<_>>()
+        })
+        .take_while(|item| item.total_cost as f64 <= distance)
+        .map(|item| (item.node, distance - item.total_cost as f64))
+        .collect::<std::collections::HashMap<_, _>>();
+
+        // faces next to the reached ones are partially reachable from their best neighbour
+        let mut frontier = std::collections::HashMap::<FaceId, (Point2<f64>, f64)>::new();
+        for (face, left) in &reached {
+            let center = waypoint(*face);
+            for (next, _) in self.walkable_neighbours(*face, radius) {
+                if reached.contains_key(&next) {
+                    continue;
+                }
+                let best = frontier.entry(next).or_insert((center, *left));
+                if left - length(center, waypoint(next)) > best.1 - length(best.0, waypoint(next)) {
+                    *best = (center, *left);
+                }
+            }
+        }
+
+        let sources = reached
+            .iter()
+            .map(|(face, left)| (*face, (waypoint(*face), *left)))
+            .chain(frontier);
+        for (face, (center, left)) in sources {
+            let vertices = self.triangulation.cdt.face(face).positions();
+            let triangle = geo::Polygon::new(
+                vertices
+                    .iter()
+                    .map(|v| geo::coord! { x: v.x, y: v.y })
+                    .collect::<Vec<_>>()
+                    .into(),
+                vec![],
+            );
+
+            if vertices.iter().all(|v| length(*v, center) <= left) {
+                region.0.push(triangle);
+            } else {
+                region
+                    .0
+                    .extend(triangle.intersection(&circle(center, left)));
+            }
+        }
+        region
+    }
+}
+
+#[test]
+fn isochrone_is_bounded_by_straight_line() {
+    use geo::{Area, Contains, CoordsIter};
+
+    let (_, store) = crate::MinimapEngine::init();
+    let from = lyon::math::Point::new(7400.0, 7400.0);
+    let budget = crate::core::GameTimer(std::time::Duration::from_secs(5));
+    let reach = 5.0 * 345.0;
+
+    let region = store.isochrone(from, budget, 345.0, 0.0);
+    let start = geo::point! { x: from.x as f64, y: from.y as f64 };
+    assert!(region.iter().any(|polygon| polygon.contains(&start)));
+
+    for coord in region.coords_iter() {
+        let distance = ((coord.x - start.x()).powi(2) + (coord.y - start.y()).powi(2)).sqrt();
+        assert!(distance <= reach + 1.0, "{coord:?} is {distance} away");
+    }
+    let area = region.unsigned_area();
+    assert!(0.0 < area && area <= std::f64::consts::PI * reach * reach);
+}
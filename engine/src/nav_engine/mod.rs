@@ -1,7 +1,16 @@
-use geo::{Contains, EuclideanDistance};
+use geo::{Contains, EuclideanDistance, InteriorPoint, LinesIter};
+use rstar::{Envelope as _, PointDistance as _};
 
 use crate::ecs::{generic::PositionComponent, UnitId};
 
+mod funnel;
+mod isochrone;
+mod raycast;
+mod walkable;
+pub use funnel::FaceId;
+pub use raycast::{Hit, Obstacle};
+pub use walkable::PointClass;
+
 #[derive(Debug, PartialEq)]
 pub enum CollisionBox {
     Polygon(geo::Polygon<f32>),
@@ -25,8 +34,155 @@ impl rstar::RTreeObject for CollisionBox {
 pub struct NavigationMap {
     pub tree: rstar::RTree<CollisionBox>,
     pub triangulation: libmap::maptri::refined::RefinedTesselation,
+    /// Constraint edges of the triangulation.
+    pub(crate) walls: rstar::RTree<rstar::primitives::Line<[f64; 2]>>,
+    /// Edges of the map polygons enclosing walls, the other polygons outline walkable areas such
+    /// as the lanes.
+    pub(crate) wall_outlines: rstar::RTree<rstar::primitives::Line<[f64; 2]>>,
+    /// Radius of the largest agent able to cross each undirected edge, indexed by edge index.
+    pub(crate) clearance: Vec<f32>,
+}
+
+impl NavigationMap {
+    pub fn new(
+        tree: rstar::RTree<CollisionBox>,
+        triangulation: libmap::maptri::refined::RefinedTesselation,
+    ) -> Self {
+        use spade::Triangulation;
+
+        let walls = rstar::RTree::bulk_load(
+            triangulation
+                .cdt
+                .undirected_edges()
+                .filter(|edge| edge.is_constraint_edge())
+                .map(|edge| {
+                    let [a, b] = edge.positions();
+                    rstar::primitives::Line::new([a.x, a.y], [b.x, b.y])
+                })
+                .collect(),
+        );
+
+        let mut nav = Self {
+            tree,
+            triangulation,
+            walls,
+            wall_outlines: rstar::RTree::new(),
+            clearance: vec![],
+        };
+
+        nav.clearance = nav
+            .triangulation
+            .cdt
+            .undirected_edges()
+            .map(|edge| {
+                let edge = edge.as_directed();
+                let walkable = [edge, edge.rev()].into_iter().all(|e| {
+                    e.face()
+                        .fix()
+                        .as_inner()
+                        .is_some_and(|f| !nav.triangulation.excluded.contains(&f))
+                });
+                if edge.is_constraint_edge() || !walkable {
+                    return 0.0;
+                }
+
+                // widest disk centered on the edge that does not overlap a wall
+                let [a, b] = edge.positions();
+                (0..=8)
+                    .map(|i| {
+                        let t = i as f64 / 8.0;
+                        nav.wall_distance([a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t])
+                    })
+                    .fold(0.0, f64::max) as f32
+            })
+            .collect();
+
+        nav.wall_outlines = rstar::RTree::bulk_load(
+            nav.tree
+                .iter()
+                .filter_map(|collision| match collision {
+                    CollisionBox::Polygon(polygon) => Some(polygon),
+                    CollisionBox::Unit { .. } => None,
+                })
+                .filter(|polygon| {
+                    polygon.interior_point().is_some_and(|point| {
+                        let point = spade::Point2::new(point.x() as f64, point.y() as f64);
+                        nav.walkable_face(point).is_none()
+                    })
+                })
+                .flat_map(|polygon| polygon.lines_iter())
+                .map(|line| {
+                    let (a, b) = (line.start, line.end);
+                    rstar::primitives::Line::new([a.x as f64, a.y as f64], [b.x as f64, b.y as f64])
+                })
+                .collect(),
+        );
+        nav
+    }
+
+    /// Units whose edge is at most `distance` away from `point`.
+    pub fn units_in_range(
+        &self,
+        point: lyon::math::Point,
+        distance: f32,
+    ) -> impl Iterator<Item = (UnitId, &PositionComponent)> {
+        self.tree
+            .locate_with_selection_function(UnitsInRange { point, distance })
+            .filter_map(|collision| match collision {
+                CollisionBox::Unit { position, guid } => Some((*guid, position)),
+                CollisionBox::Polygon(_) => None,
+            })
+    }
+
+    /// Distance from `point` to the closest wall of the navmesh.
+    pub fn wall_distance(&self, point: [f64; 2]) -> f64 {
+        self.walls
+            .nearest_neighbor(&point)
+            .map(|wall| wall.distance_2(&point).sqrt())
+            .unwrap_or(f64::INFINITY)
+    }
 }
 
+/// Endless seeded sequence of points spread over the map, the fixture of the navmesh tests.
+#[cfg(test)]
+pub(crate) fn random_points(seed: u64) -> impl Iterator<Item = lyon::math::Point> {
+    use crate::ecs::structures::MAP_BOUNDS;
+
+    let rng = fastrand::Rng::with_seed(seed);
+    std::iter::repeat_with(move || {
+        lyon::math::Point::new(
+            MAP_BOUNDS.x + rng.f32() * MAP_BOUNDS.width,
+            MAP_BOUNDS.y + rng.f32() * MAP_BOUNDS.height,
+        )
+    })
+}
+
+/// Selects units by their distance to a point, map polygons are skipped without computing their
+/// envelope.
+struct UnitsInRange {
+    point: lyon::math::Point,
+    distance: f32,
+}
+
+impl rstar::SelectionFunction<CollisionBox> for UnitsInRange {
+    fn should_unpack_parent(&self, envelope: &oobb::OOBB<f32>) -> bool {
+        let [x, y] = self.point.to_array();
+        let reach = self.distance;
+        envelope.intersects(&oobb::OOBB::from_corners(
+            [x - reach, y - reach],
+            [x + reach, y + reach],
+        ))
+    }
+
+    fn should_unpack_leaf(&self, leaf: &CollisionBox) -> bool {
+        match leaf {
+            CollisionBox::Polygon(_) => false,
+            CollisionBox::Unit { position, .. } => {
+                (position.point - self.point).length() - position.radius <= self.distance
+            }
+        }
+    }
+}
 
 impl rstar::PointDistance for CollisionBox {
     fn distance_2(&self, point: &[f32; 2]) -> f32 {
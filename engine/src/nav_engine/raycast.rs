@@ -0,0 +1,151 @@
+use lyon::math::Point;
+
+use super::{CollisionBox, NavigationMap};
+use crate::ecs::{generic::PositionComponent, UnitId};
+
+/// Obstacle stopping a ray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Obstacle {
+    Wall,
+    Unit(UnitId),
+}
+
+/// First obstacle met along a ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub point: Point,
+    /// Distance from the origin of the ray to `point`.
+    pub distance: f32,
+    pub obstacle: Obstacle,
+}
+
+fn cross(a: [f64; 2], b: [f64; 2]) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+/// Fraction of the segment `from -> to` at which it crosses the segment `start -> end`.
+fn crossing(from: [f64; 2], to: [f64; 2], start: [f64; 2], end: [f64; 2]) -> Option<f64> {
+    let d = [to[0] - from[0], to[1] - from[1]];
+    let e = [end[0] - start[0], end[1] - start[1]];
+    let f = [start[0] - from[0], start[1] - from[1]];
+
+    // parallel segments only touch at the ends of the other walls they are connected to
+    let denominator = cross(d, e);
+    if denominator == 0.0 {
+        return None;
+    }
+
+    let (t, u) = (cross(f, e) / denominator, cross(f, d) / denominator);
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+/// Fraction of the segment `from -> to` at which it enters `unit`, units overlapping `from` are
+/// never entered.
+fn entry(from: [f64; 2], to: [f64; 2], unit: &PositionComponent) -> Option<f64> {
+    let d = [to[0] - from[0], to[1] - from[1]];
+    let f = [from[0] - unit.point.x as f64, from[1] - unit.point.y as f64];
+    let radius = unit.radius as f64;
+
+    let a = d[0] * d[0] + d[1] * d[1];
+    let b = 2.0 * (f[0] * d[0] + f[1] * d[1]);
+    let c = f[0] * f[0] + f[1] * f[1] - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if c <= 0.0 || a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+impl NavigationMap {
+    /// First wall, or unit unless `ignore_units` is set, met by the segment going from `from` to
+    /// `to`. Walls are both the constraints of the triangulation and the wall polygons of the
+    /// map, the polygons outlining the lanes are not obstacles. Units overlapping `from` do not
+    /// stop the ray so that casters do not hit themselves.
+    pub fn raycast(&self, from: Point, to: Point, ignore_units: bool) -> Option<Hit> {
+        let (a, b) = ([from.x as f64, from.y as f64], [to.x as f64, to.y as f64]);
+
+        let bounds = rstar::AABB::from_corners(a, b);
+        let walls = [&self.walls, &self.wall_outlines]
+            .into_iter()
+            .flat_map(|walls| walls.locate_in_envelope_intersecting(&bounds))
+            .filter_map(|wall| crossing(a, b, wall.from, wall.to))
+            .map(|t| (t, Obstacle::Wall));
+
+        let envelope = oobb::OOBB::from_corners(from.to_array(), to.to_array());
+        let units = self
+            .tree
+            .locate_in_envelope_intersecting(&envelope)
+            .filter(|_| !ignore_units)
+            .filter_map(|collision| match collision {
+                CollisionBox::Unit { position, guid } => {
+                    entry(a, b, position).map(|t| (t, Obstacle::Unit(*guid)))
+                }
+                CollisionBox::Polygon(_) => None,
+            });
+
+        walls
+            .chain(units)
+            .min_by(|(t1, _), (t2, _)| t1.total_cmp(t2))
+            .map(|(t, obstacle)| Hit {
+                point: from.lerp(to, t as f32),
+                distance: (to - from).length() * t as f32,
+                obstacle,
+            })
+    }
+
+    /// Whether no wall stands between `from` and `to`, units do not block the sight.
+    pub fn line_of_sight(&self, from: Point, to: Point) -> bool {
+        self.raycast(from, to, true).is_none()
+    }
+}
+
+#[test]
+fn raycast_stops_at_first_obstacle() {
+    use crate::{
+        core::{Lane, Team},
+        ecs::units::minion::MinionBuilder,
+    };
+
+    let (_, mut store) = crate::MinimapEngine::init();
+
+    // a minion standing at the start of the mid lane, looked at from further down the lane
+    let path = std::sync::Arc::clone(&store.lanes[(Team::Blue, Lane::Mid)]);
+    let lengths = store.lanes.lengths(&path).unwrap();
+    let (target, from) = (
+        lengths.point_at(0.0).unwrap(),
+        lengths.point_at(400.0).unwrap(),
+    );
+    let minion = store.spawn(
+        MinionBuilder::melee()
+            .set_team(Team::Blue)
+            .set_lane(Lane::Mid)
+            .set_path(path),
+    );
+    let radius = store
+        .position
+        .get(store.get_raw_by_id(minion).unwrap().position)
+        .unwrap()
+        .1
+        .radius;
+
+    assert!(store.nav.line_of_sight(from, target));
+    let hit = store.nav.raycast(from, target, false).unwrap();
+    assert_eq!(hit.obstacle, Obstacle::Unit(minion));
+    assert!((hit.distance - ((target - from).length() - radius)).abs() < 1.0);
+
+    // from one end of the top lane to the other, across the jungle
+    let lengths = store
+        .lanes
+        .lengths(&store.lanes[(Team::Blue, Lane::Top)])
+        .unwrap();
+    let (blue, red) = (
+        lengths.point_at(0.0).unwrap(),
+        lengths.point_at(lengths.length()).unwrap(),
+    );
+    let hit = store.nav.raycast(blue, red, true).unwrap();
+    assert_eq!(hit.obstacle, Obstacle::Wall);
+    assert!(hit.distance < (red - blue).length());
+    assert!(!store.nav.line_of_sight(blue, red));
+}
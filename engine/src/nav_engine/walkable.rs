@@ -0,0 +1,212 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use lyon::math::Point;
+use spade::{Point2, PositionInTriangulation, Triangulation};
+
+use super::{CollisionBox, FaceId, NavigationMap};
+use crate::ecs::{generic::PositionComponent, UnitId};
+
+/// Attempts at stepping out of structures when looking for a walkable point.
+const STRUCTURE_ATTEMPTS: usize = 4;
+
+/// What stands at a point of the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointClass {
+    /// Outside of the walkable part of the navmesh.
+    Wall,
+    /// Inside the footprint of a turret, an inhibitor or a nexus.
+    Structure(UnitId),
+    /// On the walkable part of the navmesh.
+    Walkable,
+}
+
+impl NavigationMap {
+    /// Structure whose footprint contains `point`.
+    fn structure_at(&self, point: Point) -> Option<(UnitId, &PositionComponent)> {
+        self.tree
+            .locate_all_at_point(&point.to_array())
+            .find_map(|collision| match collision {
+                CollisionBox::Unit { position, guid } if guid.is_structure() => {
+                    Some((*guid, position))
+                }
+                _ => None,
+            })
+    }
+
+    /// What stands at `point`, structures take precedence over the navmesh they stand on.
+    pub fn classify(&self, point: Point) -> PointClass {
+        if let Some((guid, _)) = self.structure_at(point) {
+            return PointClass::Structure(guid);
+        }
+
+        // the walls are the constraints of the navmesh, the map polygons also outline the lanes
+        let on_navmesh = self
+            .walkable_face(Point2::new(point.x as f64, point.y as f64))
+            .is_some();
+        match on_navmesh {
+            true => PointClass::Walkable,
+            false => PointClass::Wall,
+        }
+    }
+
+    /// Whether an agent can stand at `point`.
+    pub fn is_walkable(&self, point: Point) -> bool {
+        self.classify(point) == PointClass::Walkable
+    }
+
+    /// Closest walkable point to `point`, the point itself when it is already walkable.
+    pub fn nearest_walkable(&self, point: Point) -> Option<Point> {
+        let mut candidate = point;
+        for _ in 0..STRUCTURE_ATTEMPTS {
+            candidate = self.nearest_navmesh_point(self.step_out(candidate))?;
+            if self.is_walkable(candidate) {
+                return Some(candidate);
+            }
+        }
+        Some(candidate)
+    }
+
+    /// Closest point outside of the structure containing `point`.
+    fn step_out(&self, point: Point) -> Point {
+        match self.structure_at(point) {
+            Some((_, structure)) => {
+                let direction = (point - structure.point)
+                    .try_normalize()
+                    .unwrap_or(lyon::math::vector(1.0, 0.0));
+                structure.point + direction * (structure.radius + 1.0)
+            }
+            None => point,
+        }
+    }
+
+    /// Closest point of a walkable face, found by visiting the faces by increasing distance to
+    /// `point`.
+    fn nearest_navmesh_point(&self, point: Point) -> Option<Point> {
+        let cdt = &self.triangulation.cdt;
+        let query = Point2::new(point.x as f64, point.y as f64);
+        if self.walkable_face(query).is_some() {
+            return Some(point);
+        }
+
+        let start = match cdt.locate(query) {
+            PositionInTriangulation::OnFace(face) => Some(face),
+            PositionInTriangulation::OnEdge(edge)
+            | PositionInTriangulation::OutsideOfConvexHull(edge) => {
+                let edge = cdt.directed_edge(edge);
+                edge.face()
+                    .fix()
+                    .as_inner()
+                    .or_else(|| edge.rev().face().fix().as_inner())
+            }
+            PositionInTriangulation::OnVertex(vertex) => cdt
+                .vertex(vertex)
+                .out_edges()
+                .find_map(|edge| edge.face().fix().as_inner()),
+            PositionInTriangulation::NoTriangulation => None,
+        }?;
+
+        // squared distances are positive so their bits are ordered like the distances
+        let key = |face: FaceId| Reverse(cdt.face(face).distance_2(query).to_bits());
+        let mut visited = HashSet::from([start]);
+        let mut queue = BinaryHeap::from([(key(start), start)]);
+        while let Some((_, face)) = queue.pop() {
+            if !self.triangulation.excluded.contains(&face) {
+                let handle = cdt.face(face);
+                let nearest = handle
+                    .adjacent_edges()
+                    .into_iter()
+                    .map(|edge| edge.nearest_point(query))
+                    .min_by(|a, b| {
+                        let (da, db) = (
+                            (a.x - query.x).powi(2) + (a.y - query.y).powi(2),
+                            (b.x - query.x).powi(2) + (b.y - query.y).powi(2),
+                        );
+                        da.total_cmp(&db)
+                    })?;
+
+                // move slightly inside the face so that the point is not on a wall
+                let center = handle.center();
+                let inside = Point::new(
+                    (nearest.x + (center.x - nearest.x) * 0.01) as f32,
+                    (nearest.y + (center.y - nearest.y) * 0.01) as f32,
+                );
+                return Some(inside);
+            }
+
+            for edge in cdt.face(face).adjacent_edges() {
+                let Some(next) = edge.rev().face().fix().as_inner() else {
+                    continue;
+                };
+                if visited.insert(next) {
+                    queue.push((key(next), next));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn objectives_in_walls_snap_to_navmesh() {
+    use crate::ecs::{
+        entity::EntityBuilder,
+        generic::pathfinding::{compute_path, Objective},
+        structures::turret::TurretIndex,
+    };
+
+    let (_, store) = crate::MinimapEngine::init();
+
+    let turret = TurretIndex::BLUE_MID_OUTER.guid();
+    let center = TurretIndex::BLUE_MID_OUTER.position().point;
+    assert_eq!(store.nav.classify(center), PointClass::Structure(turret));
+    let snapped = store.nav.nearest_walkable(center).unwrap();
+    assert!(store.nav.is_walkable(snapped));
+    assert!((snapped - center).length() >= TurretIndex::BLUE_MID_OUTER.position().radius);
+
+    // walkable points are their own nearest walkable point, structures contain what they classify
+    for point in super::random_points(0x5eed).take(256) {
+        match store.nav.classify(point) {
+            PointClass::Walkable => assert_eq!(store.nav.nearest_walkable(point), Some(point)),
+            PointClass::Structure(guid) => {
+                let structure = store.get_raw_by_id(guid).unwrap().position;
+                let structure = &store.position[structure].1;
+                assert!((point - structure.point).length() <= structure.radius);
+            }
+            PointClass::Wall => assert!(!store.nav.is_walkable(point)),
+        }
+    }
+
+    let from = store
+        .nav
+        .nearest_walkable(center + lyon::math::vector(-800.0, 800.0))
+        .unwrap();
+    let agent = PositionComponent {
+        point: from,
+        radius: 0.0,
+    };
+
+    let walls = super::random_points(0x5eed).filter(|p| store.nav.classify(*p) == PointClass::Wall);
+    for point in walls.take(16) {
+        let nearest = store.nav.nearest_walkable(point).unwrap();
+        assert!(store.nav.is_walkable(nearest), "{point:?} -> {nearest:?}");
+
+        // nothing walkable lies closer than the snapped point
+        let distance = (nearest - point).length();
+        for i in 0..16 {
+            let angle = i as f32 * std::f32::consts::TAU / 16.0;
+            let probe = point + lyon::math::vector(angle.cos(), angle.sin()) * distance * 0.9;
+            assert!(
+                !store.nav.is_walkable(probe),
+                "{probe:?} is closer than {nearest:?}"
+            );
+        }
+
+        if let Some(result) = compute_path(&agent, &Objective::Position(point), &store) {
+            let end = result.target;
+            assert!((Point::new(end.x, end.y) - nearest).length() < 1.0);
+        }
+    }
+}
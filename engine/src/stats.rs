@@ -13,6 +13,9 @@ pub trait GoldCollectable {
     fn to_last_hit(&self) -> usize {
         0
     }
+    fn experience(&self) -> f32 {
+        0.0
+    }
 }
 
 pub trait GoldCollectableIterator<T>: Iterator<Item = T>
@@ -32,6 +35,13 @@ where
     {
         self.map(|c| c.golds()).sum()
     }
+
+    fn collect_experience(self) -> f32
+    where
+        Self: Sized,
+    {
+        self.map(|c| c.experience()).sum()
+    }
 }
 
 impl<T, U> GoldCollectableIterator<T> for U
@@ -41,7 +51,43 @@ where
 {
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+/// Damage of a single hit before the resistances of its target are applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Damage {
+    Physical(f32),
+    Magic(f32),
+    True(f32),
+}
+
+impl Damage {
+    /// Health lost by a unit with `stats` when taking this damage.
+    pub fn against(&self, stats: &UnitStatistics) -> f32 {
+        self.from_attacker(&UnitStatistics::default(), stats)
+    }
+
+    /// Health lost by a unit with `target` stats when taking this damage from a unit with
+    /// `attacker` stats. Penetrations are the fraction of the positive resistances ignored.
+    pub fn from_attacker(&self, attacker: &UnitStatistics, target: &UnitStatistics) -> f32 {
+        let reduced = |damage: f32, resistance: f32, penetration: f32| {
+            if resistance >= 0.0 {
+                let resistance = resistance * (1.0 - penetration.clamp(0.0, 1.0));
+                damage * 100.0 / (100.0 + resistance)
+            } else {
+                damage * (2.0 - 100.0 / (100.0 - resistance))
+            }
+        };
+        match *self {
+            Damage::Physical(damage) => reduced(damage, target.armor, attacker.armor_penetration),
+            Damage::Magic(damage) => {
+                reduced(damage, target.magic_resist, attacker.magic_penetration)
+            }
+            Damage::True(damage) => damage,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, serde::Deserialize)]
+#[serde(default)]
 pub struct UnitStatistics {
     /* Offensive Stats */
     pub ability_power: f32,